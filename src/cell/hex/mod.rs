@@ -1,7 +1,55 @@
 use hexx::{Hex, OffsetHexMode};
 
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "bevy_reflect")]
+use bevy::prelude::Reflect;
+
 use super::Cell;
 
+/// A rotation or reflection of hexagonal cells around a pivot, using the cube coordinate rotations and reflections of
+/// [`Hex`]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bevy_reflect", derive(Reflect))]
+pub enum HexTransform {
+    /// Rotates clockwise by the given number of 60 degree steps
+    RotateCw(u32),
+    /// Rotates counter clockwise by the given number of 60 degree steps
+    RotateCcw(u32),
+    /// Reflects across the `x` (q) axis, see [`Hex::reflect_x`]
+    ReflectX,
+    /// Reflects across the `y` (r) axis, see [`Hex::reflect_y`]
+    ReflectY,
+    /// Reflects across the `z` (s) axis, see [`Hex::reflect_z`]
+    ReflectZ,
+}
+
+impl HexTransform {
+    /// The transform that undoes this one
+    pub fn inverse(self) -> HexTransform {
+        match self {
+            HexTransform::RotateCw(steps) => HexTransform::RotateCcw(steps),
+            HexTransform::RotateCcw(steps) => HexTransform::RotateCw(steps),
+            reflection => reflection,
+        }
+    }
+
+    /// Maps the cell to its position after the transform around `pivot`
+    pub fn apply(self, cell: Cell, pivot: Cell) -> Cell {
+        let (hex, pivot) = (Hex::from(cell), Hex::from(pivot));
+        let transformed = match self {
+            HexTransform::RotateCw(steps) => hex.rotate_cw_around(pivot, steps),
+            HexTransform::RotateCcw(steps) => hex.rotate_ccw_around(pivot, steps),
+            HexTransform::ReflectX => (hex - pivot).reflect_x() + pivot,
+            HexTransform::ReflectY => (hex - pivot).reflect_y() + pivot,
+            HexTransform::ReflectZ => (hex - pivot).reflect_z() + pivot,
+        };
+        transformed.into()
+    }
+}
+
 impl From<Hex> for Cell {
     fn from(value: Hex) -> Self {
         Cell::new(value.x, value.y)
@@ -15,8 +63,23 @@ impl From<Cell> for Hex {
 }
 
 impl Cell {
+    /// Offsets to the six neighbors of a hexagon, following the order of [`Hex::NEIGHBORS_COORDS`]
+    pub const HEX_OFFSETS: [Self; 6] = [
+        Self::new(1, 0),
+        Self::new(0, 1),
+        Self::new(-1, 1),
+        Self::new(-1, 0),
+        Self::new(0, -1),
+        Self::new(1, -1),
+    ];
+
     /// Converts offset coordinates into axial coordinates with the given mode.
     pub fn from_offset_coordinates(coords: [i32; 2], mode: OffsetHexMode) -> Cell {
         Hex::from_offset_coordinates(coords, mode).into()
     }
+
+    /// Rotates or reflects this cell around `pivot`. See [`HexTransform`]
+    pub fn hex_transform(self, transform: HexTransform, pivot: Cell) -> Cell {
+        transform.apply(self, pivot)
+    }
 }
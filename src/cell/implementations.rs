@@ -1,6 +1,6 @@
 use std::{
     fmt::{Display, Formatter},
-    ops::Add,
+    ops::{Add, Sub},
 };
 
 use glam::IVec2;
@@ -9,7 +9,7 @@ use super::Cell;
 
 impl Display for Cell {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        f.write_str(&*format!("x:{}, y:{}", self.x, self.y))
+        f.write_str(&format!("x:{}, y:{}", self.x, self.y))
     }
 }
 
@@ -27,8 +27,16 @@ impl Add<Cell> for Cell {
     }
 }
 
-impl Into<(usize, usize)> for Cell {
-    fn into(self) -> (usize, usize) {
-        (self.x as usize, self.y as usize)
+impl Sub<Cell> for Cell {
+    type Output = Self;
+
+    fn sub(self, rhs: Cell) -> Self::Output {
+        Cell::new(self.x.sub(rhs.x), self.y.sub(rhs.y))
+    }
+}
+
+impl From<Cell> for (usize, usize) {
+    fn from(value: Cell) -> Self {
+        (value.x as usize, value.y as usize)
     }
 }
@@ -1,9 +1,15 @@
+#[cfg(feature = "square")]
 pub(crate) mod helpers;
 #[cfg(feature = "hex")]
 pub mod hex;
 pub mod implementations;
 #[cfg(feature = "square")]
 pub mod square;
+#[cfg(any(feature = "square", feature = "hex"))]
+pub mod topology;
+pub mod transform;
+
+use glam::UVec2;
 
 #[cfg(feature = "bevy")]
 use bevy::prelude::Component;
@@ -14,6 +20,8 @@ use serde::{Deserialize, Serialize};
 #[cfg(feature = "bevy_reflect")]
 use bevy::prelude::Reflect;
 
+use transform::GridTransform;
+
 /// A position in a grid. This can represent a hexagonal or square position. Functions for both are included on it.
 ///
 /// If working with hexagonal maps its recommended to convert your Cell into [`Hex`](hex::Hex) when needed
@@ -85,4 +93,16 @@ impl Cell {
     pub const fn pos(self) -> [i32; 2] {
         [self.x, self.y]
     }
+
+    /// Maps this cell of a grid with `size` columns (x) and rows (y) to where it ends up after the grid is rotated or
+    /// flipped with [`Grid::transform`](crate::storage::grid::Grid::transform).
+    pub fn grid_transform(self, transform: GridTransform, size: UVec2) -> Cell {
+        transform.apply(self, size)
+    }
+
+    /// Maps this offset from an anchor cell to its offset after the transform, so the cells of a footprint turn with
+    /// their anchor. See [`GridTransform::apply_offset`]
+    pub fn transform_offset(self, transform: GridTransform) -> Cell {
+        transform.apply_offset(self)
+    }
 }
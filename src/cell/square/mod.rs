@@ -1,5 +1,76 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "bevy_reflect")]
+use bevy::prelude::Reflect;
+
 use super::{helpers::add_cell_arrays, Cell};
 
+/// How cells in a square grid connect to their neighbors
+#[derive(Default, Eq, Hash, PartialEq, Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bevy_reflect", derive(Reflect))]
+pub enum SquareConnectivity {
+    /// Only cells sharing an edge are neighbors. See [`Cell::SQUARE_PRIMARY_OFFSETS`]
+    #[default]
+    Four,
+    /// Cells sharing an edge or a corner are neighbors. See [`Cell::SQUARE_OFFSETS`]
+    Eight,
+}
+
+impl SquareConnectivity {
+    /// The offsets from a cell to each of its neighbors
+    pub const fn offsets(self) -> &'static [Cell] {
+        match self {
+            SquareConnectivity::Four => &Cell::SQUARE_PRIMARY_OFFSETS,
+            SquareConnectivity::Eight => &Cell::SQUARE_OFFSETS,
+        }
+    }
+}
+
+/// How distance is measured between cells in a square grid.
+///
+/// Allows logic such as pathfinding heuristics or range queries to be parameterized by metric
+#[derive(Default, Eq, Hash, PartialEq, Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bevy_reflect", derive(Reflect))]
+pub enum SquareMetric {
+    /// Distance is the number of orthogonal steps between cells. Rings and ranges are diamond shaped.
+    /// See [`Cell::manhattan_distance`]
+    #[default]
+    Manhattan,
+    /// Diagonal steps count the same as orthogonal steps. Rings and ranges are square shaped.
+    /// See [`Cell::chebyshev_distance`]
+    Chebyshev,
+    /// Straight line distance between cell centers. Rings and ranges are circular. See [`Cell::euclidean_distance`]
+    Euclidean,
+    /// Diagonal steps cost the square root of two. Rings and ranges are octagonal. See [`Cell::octile_distance`]
+    Octile,
+}
+
+impl SquareMetric {
+    /// The distance between two cells according to this metric
+    pub fn distance(self, from: Cell, to: Cell) -> f32 {
+        match self {
+            SquareMetric::Manhattan => from.manhattan_distance(to) as f32,
+            SquareMetric::Chebyshev => from.chebyshev_distance(to) as f32,
+            SquareMetric::Euclidean => from.euclidean_distance(to),
+            SquareMetric::Octile => from.octile_distance(to, std::f32::consts::SQRT_2),
+        }
+    }
+
+    /// The smallest chebyshev distance at which a cell can be `radius` away according to this metric
+    fn min_chebyshev_radius(self, radius: u32) -> u32 {
+        match self {
+            SquareMetric::Manhattan => radius.div_ceil(2),
+            SquareMetric::Chebyshev => radius,
+            SquareMetric::Euclidean | SquareMetric::Octile => {
+                (radius.saturating_sub(1) as f32 / std::f32::consts::SQRT_2) as u32
+            }
+        }
+    }
+}
+
 impl Cell {
     pub const SQUARE_PRIMARY_OFFSETS: [Self; 4] = [
         Self::new(1, 0),
@@ -17,4 +88,366 @@ impl Cell {
 
     pub const SQUARE_OFFSETS: [Self; 8] =
         add_cell_arrays(Self::SQUARE_DIAGONAL_OFFSETS, Self::SQUARE_PRIMARY_OFFSETS);
+
+    /// Iterates over the neighbors of this cell with the given connectivity
+    pub fn square_neighbors(
+        self,
+        connectivity: SquareConnectivity,
+    ) -> impl ExactSizeIterator<Item = Cell> {
+        connectivity
+            .offsets()
+            .iter()
+            .map(move |offset| self + *offset)
+    }
+
+    /// The number of orthogonal steps between this cell and `other`
+    pub fn manhattan_distance(self, other: Cell) -> u32 {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
+
+    /// The number of steps between this cell and `other` when diagonal steps are allowed and cost the same as
+    /// orthogonal steps
+    pub fn chebyshev_distance(self, other: Cell) -> u32 {
+        self.x.abs_diff(other.x).max(self.y.abs_diff(other.y))
+    }
+
+    /// The cost of walking between this cell and `other` when orthogonal steps cost 1 and diagonal steps cost
+    /// `diagonal_cost`
+    pub fn octile_distance(self, other: Cell, diagonal_cost: f32) -> f32 {
+        let dx = self.x.abs_diff(other.x);
+        let dy = self.y.abs_diff(other.y);
+        let diagonal = dx.min(dy);
+        let straight = dx.max(dy) - diagonal;
+        straight as f32 + diagonal as f32 * diagonal_cost
+    }
+
+    /// The squared straight line distance between this cell and `other`. Avoids the square root when only comparing
+    /// distances
+    pub fn euclidean_distance_squared(self, other: Cell) -> u32 {
+        let dx = self.x.abs_diff(other.x);
+        let dy = self.y.abs_diff(other.y);
+        dx * dx + dy * dy
+    }
+
+    /// The straight line distance between this cell and `other`
+    pub fn euclidean_distance(self, other: Cell) -> f32 {
+        (self.euclidean_distance_squared(other) as f32).sqrt()
+    }
+
+    /// Iterates over the cells whose distance from this cell according to `metric` is greater than `radius - 1` and at
+    /// most `radius`. For [`SquareMetric::Manhattan`] and [`SquareMetric::Chebyshev`] this is exactly `radius` away.
+    ///
+    /// Cells are visited counter clockwise, innermost first. A radius of 0 yields only this cell
+    pub fn square_ring(self, radius: u32, metric: SquareMetric) -> impl Iterator<Item = Cell> {
+        (metric.min_chebyshev_radius(radius)..=radius)
+            .flat_map(move |r| self.chebyshev_ring(r))
+            .filter(move |cell| {
+                let distance = metric.distance(self, *cell);
+                distance <= radius as f32 && distance > radius as f32 - 1.0
+            })
+    }
+
+    /// Iterates over every cell within `radius` of this cell according to `metric`, row by row
+    pub fn square_range(self, radius: u32, metric: SquareMetric) -> impl Iterator<Item = Cell> {
+        let r = radius as i32;
+        (-r..=r).flat_map(move |y| {
+            // Every metric is symmetric so each row is a contiguous span centered on this cell
+            let width = (0..=r)
+                .rev()
+                .find(|x| metric.distance(Cell::ZERO, Cell::new(*x, y)) <= radius as f32)
+                .unwrap_or(-1);
+            (-width..=width).map(move |x| self + Cell::new(x, y))
+        })
+    }
+
+    /// Iterates over every cell within `radius` of this cell according to `metric`, ring by ring starting from this
+    /// cell
+    pub fn square_spiral(self, radius: u32, metric: SquareMetric) -> impl Iterator<Item = Cell> {
+        (0..=radius).flat_map(move |r| self.square_ring(r, metric))
+    }
+
+    /// Iterates over the cells of a line from this cell to `other` using Bresenham's algorithm. Both ends are
+    /// included and every step moves to one of the 8 surrounding cells.
+    ///
+    /// When the line passes exactly between two cells the one closer to `other` is used, so the line from `other` back
+    /// to this cell may differ. See [`Cell::square_symmetric_line_to`] for a line that does not
+    pub fn square_line_to(self, other: Cell) -> impl ExactSizeIterator<Item = Cell> {
+        let length = self.chebyshev_distance(other);
+        (0..length + 1).map(move |i| bresenham_point(self, other, i, length))
+    }
+
+    /// Iterates over the cells of a line from this cell to `other` using Bresenham's algorithm, choosing the same
+    /// cells no matter which end the line is drawn from. The line from `other` back to this cell yields the same cells
+    /// in reverse order. See [`Cell::square_line_to`]
+    pub fn square_symmetric_line_to(self, other: Cell) -> impl ExactSizeIterator<Item = Cell> {
+        let length = self.chebyshev_distance(other);
+        let forward = (self.x, self.y) <= (other.x, other.y);
+        let (start, end) = if forward {
+            (self, other)
+        } else {
+            (other, self)
+        };
+        (0..length + 1).map(move |i| {
+            let i = if forward { i } else { length - i };
+            bresenham_point(start, end, i, length)
+        })
+    }
+
+    /// Iterates over every cell touched by the straight line between the centers of this cell and `other`, both
+    /// ends included.
+    ///
+    /// Every step moves to one of the 4 orthogonal neighbors, except where the line passes exactly through a corner.
+    /// There the line touches all four cells around the corner, so both cells beside the corner are included before
+    /// the cell diagonally across it
+    pub fn square_supercover_line_to(self, other: Cell) -> impl Iterator<Item = Cell> {
+        let mut current = self;
+        std::iter::once(self).chain(self.square_line_steps(other).flat_map(move |step| {
+            let corner = current;
+            current = current + step;
+            if step.x != 0 && step.y != 0 {
+                let cells = [
+                    corner + Cell::new(step.x, 0),
+                    corner + Cell::new(0, step.y),
+                    current,
+                ];
+                cells.into_iter().take(3)
+            } else {
+                [current; 3].into_iter().take(1)
+            }
+        }))
+    }
+
+    /// Walks from this cell to `other` along the straight line between their centers, yielding every cell the line
+    /// passes through. Steps diagonally when the line passes exactly through a corner
+    pub(crate) fn square_walk_to(self, other: Cell) -> impl Iterator<Item = Cell> {
+        let mut current = self;
+        std::iter::once(self).chain(self.square_line_steps(other).map(move |step| {
+            current = current + step;
+            current
+        }))
+    }
+
+    /// The offsets of each step along the straight line between the centers of this cell and `other`. Steps are
+    /// orthogonal, or diagonal where the line passes exactly through a corner
+    fn square_line_steps(self, other: Cell) -> impl Iterator<Item = Cell> {
+        let (nx, ny) = (self.x.abs_diff(other.x), self.y.abs_diff(other.y));
+        let step = Cell::new((other.x - self.x).signum(), (other.y - self.y).signum());
+        let (mut ix, mut iy) = (0u32, 0u32);
+        std::iter::from_fn(move || {
+            if ix >= nx && iy >= ny {
+                return None;
+            }
+            // Compares the distance along the line to the next vertical and horizontal cell edges
+            let decision = (1 + 2 * ix as i64) * ny as i64 - (1 + 2 * iy as i64) * nx as i64;
+            if decision == 0 {
+                ix += 1;
+                iy += 1;
+                Some(step)
+            } else if decision < 0 {
+                ix += 1;
+                Some(Cell::new(step.x, 0))
+            } else {
+                iy += 1;
+                Some(Cell::new(0, step.y))
+            }
+        })
+    }
+
+    /// Walks counter clockwise around the square of cells exactly `radius` away in chebyshev distance
+    fn chebyshev_ring(self, radius: u32) -> impl ExactSizeIterator<Item = Cell> {
+        let r = radius as i32;
+        // Each side of the ring starts at a corner and walks in a direction for `2 * r` steps
+        let sides = [
+            (Cell::new(r, -r), Cell::new(0, 1)),
+            (Cell::new(r, r), Cell::new(-1, 0)),
+            (Cell::new(-r, r), Cell::new(0, -1)),
+            (Cell::new(-r, -r), Cell::new(1, 0)),
+        ];
+        let side_length = (2 * r).max(1);
+        let count = if radius == 0 { 1 } else { 8 * radius as usize };
+        (0..count).map(move |i| {
+            let i = i as i32;
+            let (corner, direction) = sides[(i / side_length) as usize];
+            let step = i % side_length;
+            self + corner + Cell::new(direction.x * step, direction.y * step)
+        })
+    }
+}
+
+/// The cell at step `i` of a Bresenham line `length` steps long
+fn bresenham_point(from: Cell, to: Cell, i: u32, length: u32) -> Cell {
+    if length == 0 {
+        return from;
+    }
+    // Rounds i * delta / length to the nearest whole number with ties going towards `to`
+    let along = |delta: i32| {
+        let (i, length) = (i as i64, length as i64);
+        let magnitude = (2 * i * delta.unsigned_abs() as i64 + length) / (2 * length);
+        (magnitude * delta.signum() as i64) as i32
+    };
+    from + Cell::new(along(to.x - from.x), along(to.y - from.y))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::{SquareConnectivity, SquareMetric};
+    use crate::cell::Cell;
+
+    #[test]
+    fn test_neighbors() {
+        let cell = Cell::new(3, -2);
+        let four: Vec<Cell> = cell.square_neighbors(SquareConnectivity::Four).collect();
+        assert_eq!(
+            four,
+            vec![
+                Cell::new(4, -2),
+                Cell::new(3, -1),
+                Cell::new(2, -2),
+                Cell::new(3, -3)
+            ]
+        );
+        assert_eq!(cell.square_neighbors(SquareConnectivity::Eight).len(), 8);
+    }
+
+    #[test]
+    fn test_ring() {
+        let center = Cell::new(1, 1);
+        assert_eq!(
+            center
+                .square_ring(0, SquareMetric::Manhattan)
+                .collect::<Vec<_>>(),
+            vec![center]
+        );
+
+        for radius in 1..5 {
+            let manhattan: HashSet<Cell> = center
+                .square_ring(radius, SquareMetric::Manhattan)
+                .collect();
+            assert_eq!(manhattan.len(), 4 * radius as usize);
+            assert!(manhattan.iter().all(|cell| {
+                let offset = *cell - center;
+                (offset.x.abs() + offset.y.abs()) as u32 == radius
+            }));
+
+            let chebyshev: HashSet<Cell> = center
+                .square_ring(radius, SquareMetric::Chebyshev)
+                .collect();
+            assert_eq!(chebyshev.len(), 8 * radius as usize);
+            assert!(chebyshev.iter().all(|cell| {
+                let offset = *cell - center;
+                offset.x.abs().max(offset.y.abs()) as u32 == radius
+            }));
+        }
+    }
+
+    #[test]
+    fn test_distances() {
+        let a = Cell::new(-1, 2);
+        let b = Cell::new(3, -1);
+        assert_eq!(a.manhattan_distance(b), 7);
+        assert_eq!(a.chebyshev_distance(b), 4);
+        assert_eq!(a.euclidean_distance_squared(b), 25);
+        assert_eq!(a.euclidean_distance(b), 5.0);
+        assert_eq!(a.octile_distance(b, 1.5), 5.5);
+        assert_eq!(b.octile_distance(a, 2.0), a.manhattan_distance(b) as f32);
+        assert_eq!(SquareMetric::Chebyshev.distance(a, b), 4.0);
+    }
+
+    #[test]
+    fn test_range_and_spiral() {
+        let center = Cell::new(-2, 4);
+        for metric in [
+            SquareMetric::Manhattan,
+            SquareMetric::Chebyshev,
+            SquareMetric::Euclidean,
+            SquareMetric::Octile,
+        ] {
+            let range: HashSet<Cell> = center.square_range(3, metric).collect();
+            let spiral: Vec<Cell> = center.square_spiral(3, metric).collect();
+            assert_eq!(spiral.len(), range.len());
+            assert_eq!(spiral[0], center);
+            assert_eq!(spiral.into_iter().collect::<HashSet<_>>(), range);
+            assert!(range
+                .iter()
+                .all(|cell| metric.distance(center, *cell) <= 3.0));
+        }
+        assert_eq!(center.square_range(3, SquareMetric::Manhattan).count(), 25);
+        assert_eq!(center.square_range(3, SquareMetric::Chebyshev).count(), 49);
+        assert_eq!(center.square_range(3, SquareMetric::Euclidean).count(), 29);
+    }
+
+    #[test]
+    fn test_lines() {
+        let a = Cell::new(-2, 1);
+        for b in a.square_range(6, SquareMetric::Chebyshev) {
+            let line: Vec<Cell> = a.square_line_to(b).collect();
+            assert_eq!(line.len(), a.chebyshev_distance(b) as usize + 1);
+            assert_eq!(line.first(), Some(&a));
+            assert_eq!(line.last(), Some(&b));
+            assert!(line.windows(2).all(|w| w[0].chebyshev_distance(w[1]) == 1));
+
+            let walk: Vec<Cell> = a.square_walk_to(b).collect();
+            assert_eq!(walk.last(), Some(&b));
+            assert!(walk.windows(2).all(|w| w[0].chebyshev_distance(w[1]) == 1));
+
+            // The supercover adds both cells beside every corner the walk steps diagonally across
+            let supercover: Vec<Cell> = a.square_supercover_line_to(b).collect();
+            let corners = walk
+                .windows(2)
+                .filter(|w| w[0].manhattan_distance(w[1]) == 2)
+                .count();
+            assert_eq!(supercover.len(), walk.len() + 2 * corners);
+            assert_eq!(supercover.last(), Some(&b));
+            assert!(walk.iter().all(|cell| supercover.contains(cell)));
+            assert!(supercover
+                .windows(2)
+                .all(|w| w[0].chebyshev_distance(w[1]) == 1));
+
+            let mut forward: Vec<Cell> = a.square_symmetric_line_to(b).collect();
+            let backward: Vec<Cell> = b.square_symmetric_line_to(a).collect();
+            assert_eq!(forward.first(), Some(&a));
+            forward.reverse();
+            assert_eq!(forward, backward);
+        }
+
+        assert_eq!(
+            Cell::ZERO
+                .square_line_to(Cell::new(4, 1))
+                .collect::<Vec<_>>(),
+            vec![
+                Cell::new(0, 0),
+                Cell::new(1, 0),
+                Cell::new(2, 1),
+                Cell::new(3, 1),
+                Cell::new(4, 1)
+            ]
+        );
+        assert_eq!(
+            Cell::ZERO
+                .square_supercover_line_to(Cell::new(2, 1))
+                .collect::<Vec<_>>(),
+            vec![
+                Cell::new(0, 0),
+                Cell::new(1, 0),
+                Cell::new(1, 1),
+                Cell::new(2, 1)
+            ]
+        );
+        assert_eq!(
+            Cell::ZERO
+                .square_supercover_line_to(Cell::new(2, -2))
+                .collect::<Vec<_>>(),
+            vec![
+                Cell::new(0, 0),
+                Cell::new(1, 0),
+                Cell::new(0, -1),
+                Cell::new(1, -1),
+                Cell::new(2, -1),
+                Cell::new(1, -2),
+                Cell::new(2, -2)
+            ]
+        );
+    }
 }
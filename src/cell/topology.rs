@@ -0,0 +1,51 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "bevy_reflect")]
+use bevy::prelude::Reflect;
+
+#[cfg(feature = "square")]
+use super::square::SquareConnectivity;
+use super::Cell;
+
+/// Which cells neighbor each other. Allows algorithms to be written once over hexagonal and square maps
+#[derive(Eq, Hash, PartialEq, Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bevy_reflect", derive(Reflect))]
+pub enum Topology {
+    /// Hexagonal neighbors using axial coordinates. See [`Cell::HEX_OFFSETS`]
+    #[cfg(feature = "hex")]
+    Hex,
+    /// Square neighbors with the given connectivity
+    #[cfg(feature = "square")]
+    Square(SquareConnectivity),
+}
+
+impl Topology {
+    /// The offsets from a cell to each of its neighbors
+    pub const fn offsets(self) -> &'static [Cell] {
+        match self {
+            #[cfg(feature = "hex")]
+            Topology::Hex => &Cell::HEX_OFFSETS,
+            #[cfg(feature = "square")]
+            Topology::Square(connectivity) => connectivity.offsets(),
+        }
+    }
+
+    /// Iterates over the neighbors of the given cell
+    pub fn neighbors(self, cell: Cell) -> impl ExactSizeIterator<Item = Cell> {
+        self.offsets().iter().map(move |offset| cell + *offset)
+    }
+
+    /// The least number of steps between two cells
+    pub fn distance(self, from: Cell, to: Cell) -> u32 {
+        match self {
+            #[cfg(feature = "hex")]
+            Topology::Hex => hexx::Hex::from(from).unsigned_distance_to(to.into()),
+            #[cfg(feature = "square")]
+            Topology::Square(SquareConnectivity::Four) => from.manhattan_distance(to),
+            #[cfg(feature = "square")]
+            Topology::Square(SquareConnectivity::Eight) => from.chebyshev_distance(to),
+        }
+    }
+}
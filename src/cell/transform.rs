@@ -0,0 +1,144 @@
+//! Rotations and reflections of rectangular grids of cells
+
+use glam::UVec2;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "bevy_reflect")]
+use bevy::prelude::Reflect;
+
+use super::Cell;
+
+/// A rotation or reflection of a [`Grid`](crate::storage::grid::Grid), see
+/// [`Grid::transform`](crate::storage::grid::Grid::transform).
+///
+/// Cells map to grid positions with `x` as the column and `y` as the row, with row 0 at the top.
+/// [`GridTransform::apply`] maps a cell of the original grid to where it ends up, so cells stored alongside a grid,
+/// such as the anchor of a prefab room, can be moved together with it.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bevy_reflect", derive(Reflect))]
+pub enum GridTransform {
+    /// A quarter turn clockwise
+    RotateCw,
+    /// A quarter turn counter clockwise
+    RotateCcw,
+    /// A half turn
+    Rotate180,
+    /// Mirrors left to right, reversing each row
+    FlipHorizontal,
+    /// Mirrors top to bottom, reversing the order of the rows
+    FlipVertical,
+}
+
+impl GridTransform {
+    /// The transform that undoes this one
+    pub fn inverse(self) -> GridTransform {
+        match self {
+            GridTransform::RotateCw => GridTransform::RotateCcw,
+            GridTransform::RotateCcw => GridTransform::RotateCw,
+            other => other,
+        }
+    }
+
+    /// The columns (x) and rows (y) of a grid of the given size after the transform
+    pub fn transformed_size(self, size: UVec2) -> UVec2 {
+        match self {
+            GridTransform::RotateCw | GridTransform::RotateCcw => UVec2::new(size.y, size.x),
+            _ => size,
+        }
+    }
+
+    /// Maps a cell of a grid with `size` columns (x) and rows (y) to its position after the transform. Cells outside
+    /// the grid are mapped the same way
+    ///
+    /// ```
+    /// use glam::UVec2;
+    /// use lettuces::cell::Cell;
+    /// use lettuces::cell::transform::GridTransform;
+    /// let size = UVec2::new(3, 2);
+    /// assert_eq!(GridTransform::RotateCw.apply(Cell::new(0, 0), size), Cell::new(1, 0));
+    /// assert_eq!(GridTransform::FlipVertical.apply(Cell::new(2, 0), size), Cell::new(2, 1));
+    /// ```
+    pub fn apply(self, cell: Cell, size: UVec2) -> Cell {
+        let (last_x, last_y) = (size.x as i32 - 1, size.y as i32 - 1);
+        match self {
+            GridTransform::RotateCw => Cell::new(last_y - cell.y, cell.x),
+            GridTransform::RotateCcw => Cell::new(cell.y, last_x - cell.x),
+            GridTransform::Rotate180 => Cell::new(last_x - cell.x, last_y - cell.y),
+            GridTransform::FlipHorizontal => Cell::new(last_x - cell.x, cell.y),
+            GridTransform::FlipVertical => Cell::new(cell.x, last_y - cell.y),
+        }
+    }
+
+    /// Maps an offset from an anchor cell to its offset after the transform, turning around [`Cell::ORIGIN`] instead
+    /// of a grid. Used to move the cells of a footprint together with the anchor they are relative to
+    ///
+    /// ```
+    /// use lettuces::cell::Cell;
+    /// use lettuces::cell::transform::GridTransform;
+    /// assert_eq!(GridTransform::RotateCw.apply_offset(Cell::new(1, 0)), Cell::new(0, 1));
+    /// assert_eq!(GridTransform::FlipHorizontal.apply_offset(Cell::new(2, 1)), Cell::new(-2, 1));
+    /// ```
+    pub fn apply_offset(self, offset: Cell) -> Cell {
+        match self {
+            GridTransform::RotateCw => Cell::new(-offset.y, offset.x),
+            GridTransform::RotateCcw => Cell::new(offset.y, -offset.x),
+            GridTransform::Rotate180 => Cell::new(-offset.x, -offset.y),
+            GridTransform::FlipHorizontal => Cell::new(-offset.x, offset.y),
+            GridTransform::FlipVertical => Cell::new(offset.x, -offset.y),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::UVec2;
+
+    use crate::cell::Cell;
+    use crate::storage::{grid::Grid, GridStorage};
+
+    use super::GridTransform;
+
+    #[test]
+    fn test_grid_transform() {
+        // An L shaped footprint around its anchor, rotated with the room it sits in
+        let footprint = [Cell::ZERO, Cell::X, Cell::Y, Cell::new(0, 2)];
+        let rotated: Vec<Cell> = footprint
+            .iter()
+            .map(|offset| offset.transform_offset(GridTransform::RotateCw))
+            .collect();
+        assert_eq!(
+            rotated,
+            vec![Cell::ZERO, Cell::Y, Cell::NEG_X, Cell::new(-2, 0)]
+        );
+
+        let size = UVec2::new(4, 3);
+        let mut room = Grid::new(3, 4);
+        let anchor = Cell::new(1, 0);
+        for offset in footprint {
+            GridStorage::set(&mut room, anchor + offset, 1);
+        }
+        for transform in [
+            GridTransform::RotateCw,
+            GridTransform::RotateCcw,
+            GridTransform::Rotate180,
+            GridTransform::FlipHorizontal,
+            GridTransform::FlipVertical,
+        ] {
+            let moved = anchor.grid_transform(transform, size);
+            let turned = room.transformed(transform);
+            let offsets = footprint.map(|offset| offset.transform_offset(transform));
+            assert_eq!(
+                transform
+                    .inverse()
+                    .apply(moved, transform.transformed_size(size)),
+                anchor
+            );
+            assert!(offsets
+                .iter()
+                .all(|offset| GridStorage::get(&turned, moved + *offset) == Some(&1)));
+        }
+    }
+}
@@ -0,0 +1,61 @@
+//! Errors returned by the fallible `try_` methods of the storages, for handling bad data from save files or editors
+//! without panicking.
+
+use std::fmt::{Display, Formatter};
+
+use crate::cell::Cell;
+
+/// An error accessing or building a storage
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum LettucesError {
+    /// The cell is outside the storage. For a [`Grid`](crate::storage::grid::Grid) the cell uses the column as `x`
+    /// and the row as `y`
+    OutOfBounds(Cell),
+    /// A row of the input data has a different length than the rows before it
+    Ragged {
+        row: usize,
+        expected: usize,
+        found: usize,
+    },
+    /// The input data has the wrong number of elements
+    SizeMismatch { expected: usize, found: usize },
+    /// The input data has no elements
+    Empty,
+}
+
+impl Display for LettucesError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LettucesError::OutOfBounds(cell) => write!(f, "cell ({cell}) is outside the storage"),
+            LettucesError::Ragged {
+                row,
+                expected,
+                found,
+            } => write!(
+                f,
+                "row {row} has length {found} but should have length {expected}"
+            ),
+            LettucesError::SizeMismatch { expected, found } => {
+                write!(f, "expected {expected} elements but found {found}")
+            }
+            LettucesError::Empty => f.write_str("input data is empty"),
+        }
+    }
+}
+
+impl std::error::Error for LettucesError {}
+
+/// Returns the length shared by every row of the data, or [`LettucesError::Ragged`] for the first row with a different
+/// length
+#[cfg(any(feature = "square", feature = "hex"))]
+pub(crate) fn row_length<T>(data: &[Vec<T>]) -> Result<usize, LettucesError> {
+    let expected = data.first().map_or(0, |row| row.len());
+    match data.iter().position(|row| row.len() != expected) {
+        Some(row) => Err(LettucesError::Ragged {
+            row,
+            expected,
+            found: data[row].len(),
+        }),
+        None => Ok(expected),
+    }
+}
@@ -0,0 +1,186 @@
+use std::collections::HashSet;
+
+use hexx::Hex;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "bevy_reflect")]
+use bevy::prelude::Reflect;
+
+use crate::{cell::Cell, storage::GridStorage};
+
+/// How a single hexagon affects sight
+#[derive(Default, PartialEq, Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bevy_reflect", derive(Reflect))]
+pub struct HexSight {
+    /// The height of the top of the hexagon, including anything standing on it such as a wall
+    pub elevation: f32,
+    /// If set to `true` the hexagon blocks sight at any height, for example thick smoke
+    pub opaque: bool,
+}
+
+/// Field of view and line of sight for hexagonal maps with elevation.
+///
+/// Sight is a straight line from the observer's eyes, `observer_height` above the top of their hexagon, to the top
+/// of the target hexagon. Any hexagon in between that is opaque or rises above that line blocks it. Higher hexagons
+/// hide lower hexagons behind them while tall observers can see over low walls
+#[derive(PartialEq, Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bevy_reflect", derive(Reflect))]
+pub struct HexFov {
+    /// The furthest distance in hexagons that can be seen
+    pub radius: u32,
+    /// The height of the observer's eyes above the top of their hexagon
+    pub observer_height: f32,
+}
+
+impl Default for HexFov {
+    fn default() -> Self {
+        Self {
+            radius: 10,
+            observer_height: 1.0,
+        }
+    }
+}
+
+impl HexFov {
+    /// Returns whether `to` can be seen from `from`, reading the [`HexSight`] of each hexagon from a storage.
+    ///
+    /// The line between the hexagons is drawn with [`Hex::line_to`]. Cells outside the storage block sight and can't be
+    /// seen. The radius is not checked
+    pub fn has_line_of_sight<T>(
+        &self,
+        storage: &impl GridStorage<T>,
+        from: Cell,
+        to: Cell,
+        sight: impl Fn(&T) -> HexSight,
+    ) -> bool {
+        let (Some(origin), Some(target)) = (storage.get(from), storage.get(to)) else {
+            return false;
+        };
+        let eye = sight(origin).elevation + self.observer_height;
+        let target = sight(target).elevation;
+
+        let line = Hex::from(from).line_to(to.into());
+        let length = line.len().saturating_sub(1).max(1) as f32;
+        for (step, hex) in line.enumerate() {
+            let cell = Cell::from(hex);
+            if cell == from || cell == to {
+                continue;
+            }
+            let Some(data) = storage.get(cell) else {
+                return false;
+            };
+            let blocker = sight(data);
+            let line_height = eye + (target - eye) * (step as f32 / length);
+            if blocker.opaque || blocker.elevation > line_height {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Returns every cell within the radius that can be seen from `origin`, including `origin` itself. See
+    /// [`HexFov::has_line_of_sight`]
+    pub fn visible<T>(
+        &self,
+        storage: &impl GridStorage<T>,
+        origin: Cell,
+        sight: impl Fn(&T) -> HexSight,
+    ) -> HashSet<Cell> {
+        Hex::from(origin)
+            .range(self.radius)
+            .map(Cell::from)
+            .filter(|cell| self.has_line_of_sight(storage, origin, *cell, &sight))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hexx::{Hex, HexOrientation};
+
+    use crate::{cell::Cell, storage::hex::HexRectangleStorage};
+
+    use super::{HexFov, HexSight};
+
+    fn flat_map() -> HexRectangleStorage<HexSight> {
+        HexRectangleStorage::new(9, 9, HexOrientation::Pointy)
+    }
+
+    #[test]
+    fn test_open_map() {
+        let map = flat_map();
+        let fov = HexFov {
+            radius: 2,
+            ..Default::default()
+        };
+        let visible = fov.visible(&map, Cell::new(2, 4), |sight| *sight);
+        assert_eq!(visible.len(), Hex::range_count(2) as usize);
+    }
+
+    #[test]
+    fn test_walls_and_observer_height() {
+        let mut map = flat_map();
+        let from = Cell::new(0, 4);
+        let to = Cell::new(4, 4);
+        map.set(
+            Cell::new(2, 4),
+            HexSight {
+                elevation: 1.0,
+                opaque: false,
+            },
+        );
+
+        let short = HexFov {
+            observer_height: 0.5,
+            ..Default::default()
+        };
+        assert!(!short.has_line_of_sight(&map, from, to, |sight| *sight));
+        // The wall itself is visible
+        assert!(short.has_line_of_sight(&map, from, Cell::new(2, 4), |sight| *sight));
+
+        let tall = HexFov {
+            observer_height: 3.0,
+            ..Default::default()
+        };
+        assert!(tall.has_line_of_sight(&map, from, to, |sight| *sight));
+
+        map.set(
+            Cell::new(2, 4),
+            HexSight {
+                elevation: 0.0,
+                opaque: true,
+            },
+        );
+        assert!(!tall.has_line_of_sight(&map, from, to, |sight| *sight));
+    }
+
+    #[test]
+    fn test_cliff_hides_lower_cells() {
+        let mut map = flat_map();
+        // A plateau one hexagon wide
+        map.set(
+            Cell::new(2, 4),
+            HexSight {
+                elevation: 5.0,
+                opaque: false,
+            },
+        );
+        map.set(
+            Cell::new(3, 4),
+            HexSight {
+                elevation: 2.0,
+                opaque: false,
+            },
+        );
+        let fov = HexFov::default();
+
+        // From the plateau the lower hexagons are visible
+        assert!(fov.has_line_of_sight(&map, Cell::new(2, 4), Cell::new(5, 4), |sight| *sight));
+        // From the ground the plateau hides the lower hexagons behind it
+        assert!(!fov.has_line_of_sight(&map, Cell::new(0, 4), Cell::new(3, 4), |sight| *sight));
+        assert!(!fov.has_line_of_sight(&map, Cell::new(0, 4), Cell::new(5, 4), |sight| *sight));
+    }
+}
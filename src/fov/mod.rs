@@ -0,0 +1,6 @@
+//! Field of view calculations, finding which cells can be seen from a cell.
+
+#[cfg(feature = "hex")]
+pub mod hex;
+#[cfg(feature = "square")]
+pub mod square;
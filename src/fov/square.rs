@@ -0,0 +1,357 @@
+use std::collections::HashSet;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "bevy_reflect")]
+use bevy::prelude::Reflect;
+
+use crate::{cell::square::SquareMetric, cell::Cell, storage::GridStorage};
+
+/// Field of view for square grids using symmetric shadowcasting.
+///
+/// Visibility is symmetric, if a cell can see another then that cell can see it back. See
+/// [Symmetric Shadowcasting](https://www.albertford.com/shadowcasting/)
+#[derive(PartialEq, Eq, Hash, Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bevy_reflect", derive(Reflect))]
+pub struct SquareFov {
+    /// The furthest distance that can be seen when computing against a storage. Without a radius the view is only
+    /// bounded by the storage. The closure based [`SquareFov::compute`] takes its own radius instead
+    pub radius: Option<u32>,
+    /// How the radius is measured
+    pub metric: SquareMetric,
+    /// If set to `true` opaque cells facing the origin are visible. Otherwise only transparent cells are visible
+    pub light_walls: bool,
+}
+
+impl Default for SquareFov {
+    fn default() -> Self {
+        Self {
+            radius: None,
+            metric: SquareMetric::Euclidean,
+            light_walls: true,
+        }
+    }
+}
+
+/// A row of cells in one quadrant, `depth` cells away from the origin and bounded by two slopes
+#[derive(Copy, Clone)]
+struct Row {
+    depth: i64,
+    start_slope: Slope,
+    end_slope: Slope,
+}
+
+/// An exact fraction, so that symmetric visibility does not suffer from rounding
+#[derive(Copy, Clone)]
+struct Slope {
+    num: i64,
+    den: i64,
+}
+
+impl Row {
+    fn columns(&self) -> std::ops::RangeInclusive<i64> {
+        // Rounds depth * slope to the nearest column, with ties going inwards
+        let min = (2 * self.depth * self.start_slope.num + self.start_slope.den)
+            .div_euclid(2 * self.start_slope.den);
+        let max = -(self.end_slope.den - 2 * self.depth * self.end_slope.num)
+            .div_euclid(2 * self.end_slope.den);
+        min..=max
+    }
+
+    /// Whether the center of the cell at `col` is inside the row's slopes
+    fn is_symmetric(&self, col: i64) -> bool {
+        col * self.start_slope.den >= self.depth * self.start_slope.num
+            && col * self.end_slope.den <= self.depth * self.end_slope.num
+    }
+
+    fn next(&self) -> Row {
+        Row {
+            depth: self.depth + 1,
+            ..*self
+        }
+    }
+}
+
+/// The slope to the left edge of the cell at `depth`, `col`
+fn slope(depth: i64, col: i64) -> Slope {
+    Slope {
+        num: 2 * col - 1,
+        den: 2 * depth,
+    }
+}
+
+impl SquareFov {
+    /// Computes the field of view from `origin` up to `radius`, calling `mark_visible` for every visible cell. Cells
+    /// may be marked more than once.
+    ///
+    /// `is_opaque` returns whether a cell blocks sight. Nothing else bounds an open map, so `radius` is used in place of
+    /// [`SquareFov::radius`]
+    pub fn compute(
+        &self,
+        origin: Cell,
+        radius: u32,
+        is_opaque: impl Fn(Cell) -> bool,
+        mut mark_visible: impl FnMut(Cell),
+    ) {
+        self.cast(
+            origin,
+            Some(radius),
+            &mut (),
+            &|_, cell| is_opaque(cell),
+            &mut |_, cell| mark_visible(cell),
+        );
+    }
+
+    /// Computes the field of view from `origin` up to `radius` and returns every visible cell. See
+    /// [`SquareFov::compute`]
+    pub fn visible_set(
+        &self,
+        origin: Cell,
+        radius: u32,
+        is_opaque: impl Fn(Cell) -> bool,
+    ) -> HashSet<Cell> {
+        let mut visible = HashSet::new();
+        self.compute(origin, radius, is_opaque, |cell| {
+            visible.insert(cell);
+        });
+        visible
+    }
+
+    /// Computes the field of view from `origin` reading opacity from a storage, returning every visible cell.
+    ///
+    /// `is_opaque` returns whether a cell with the given data blocks sight. Cells outside the storage block sight and
+    /// are never visible, so the view does not need a radius
+    pub fn visible_in_storage<T>(
+        &self,
+        storage: &impl GridStorage<T>,
+        origin: Cell,
+        is_opaque: impl Fn(&T) -> bool,
+    ) -> HashSet<Cell> {
+        let mut visible = HashSet::new();
+        self.cast(
+            origin,
+            self.radius,
+            &mut visible,
+            &|_, cell| storage.get(cell).is_none_or(&is_opaque),
+            &mut |visible, cell| {
+                if storage.contains(cell) {
+                    visible.insert(cell);
+                }
+            },
+        );
+        visible
+    }
+
+    /// Computes the field of view from `origin`, setting every visible cell inside `visible` to true. Cells that
+    /// are not visible are left unchanged, so clear the storage first when reusing it.
+    ///
+    /// Cells outside `visible` block sight, so the view does not need a radius. See [`SquareFov::visible_in_storage`]
+    pub fn compute_into(
+        &self,
+        origin: Cell,
+        is_opaque: impl Fn(Cell) -> bool,
+        visible: &mut impl GridStorage<bool>,
+    ) {
+        self.cast(
+            origin,
+            self.radius,
+            visible,
+            &|visible, cell| !visible.contains(cell) || is_opaque(cell),
+            &mut |visible, cell| {
+                visible.set(cell, true);
+            },
+        );
+    }
+
+    /// Scans every quadrant around `origin`. `state` is handed to both callbacks so that they can share a storage.
+    ///
+    /// Rows still to be scanned are kept on a stack rather than recursed into, so a large unbounded view does not
+    /// grow the call stack with its depth
+    fn cast<V: ?Sized>(
+        &self,
+        origin: Cell,
+        radius: Option<u32>,
+        state: &mut V,
+        is_opaque: &impl Fn(&V, Cell) -> bool,
+        mark_visible: &mut impl FnMut(&mut V, Cell),
+    ) {
+        mark_visible(state, origin);
+        // Each quadrant maps a row depth and column to a cell
+        let quadrants: [fn(Cell, i64, i64) -> Cell; 4] = [
+            |o, depth, col| Cell::new(o.x + col as i32, o.y - depth as i32),
+            |o, depth, col| Cell::new(o.x + depth as i32, o.y + col as i32),
+            |o, depth, col| Cell::new(o.x + col as i32, o.y + depth as i32),
+            |o, depth, col| Cell::new(o.x - depth as i32, o.y + col as i32),
+        ];
+        let mut rows = Vec::new();
+        for transform in quadrants {
+            rows.push(Row {
+                depth: 1,
+                start_slope: Slope { num: -1, den: 1 },
+                end_slope: Slope { num: 1, den: 1 },
+            });
+            while let Some(mut row) = rows.pop() {
+                if radius.is_some_and(|radius| row.depth > radius as i64) {
+                    continue;
+                }
+                let mut previous_wall: Option<bool> = None;
+                for col in row.columns() {
+                    let cell = transform(origin, row.depth, col);
+                    let wall = is_opaque(state, cell);
+                    let in_radius = radius
+                        .is_none_or(|radius| self.metric.distance(origin, cell) <= radius as f32);
+
+                    if in_radius && ((wall && self.light_walls) || (!wall && row.is_symmetric(col)))
+                    {
+                        mark_visible(state, cell);
+                    }
+                    if previous_wall == Some(true) && !wall {
+                        row.start_slope = slope(row.depth, col);
+                    }
+                    if previous_wall == Some(false) && wall {
+                        let mut next = row.next();
+                        next.end_slope = slope(row.depth, col);
+                        rows.push(next);
+                    }
+                    previous_wall = Some(wall);
+                }
+                if previous_wall == Some(false) {
+                    rows.push(row.next());
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::cell::{square::SquareMetric, Cell};
+    use crate::storage::{grid::Grid, square::SquareStorage, GridStorage};
+
+    use super::SquareFov;
+
+    /// Parses a map where `#` is a wall
+    fn parse(map: &[&str]) -> SquareStorage<bool> {
+        SquareStorage::new_from_vec(
+            map.iter()
+                .map(|row| row.chars().map(|c| c == '#').collect())
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_open_room() {
+        let map = parse(&["#####", "#...#", "#...#", "#...#", "#####"]);
+        let visible = SquareFov::default().visible_in_storage(&map, Cell::new(2, 2), |wall| *wall);
+        assert_eq!(visible.len(), 25);
+
+        let fov = SquareFov {
+            light_walls: false,
+            ..Default::default()
+        };
+        let visible = fov.visible_in_storage(&map, Cell::new(2, 2), |wall| *wall);
+        assert_eq!(visible.len(), 9);
+    }
+
+    #[test]
+    fn test_pillar_shadow() {
+        let map = parse(&[
+            "#########",
+            "#.......#",
+            "#.......#",
+            "#...#...#",
+            "#.......#",
+            "#########",
+        ]);
+        let visible = SquareFov::default().visible_in_storage(&map, Cell::new(4, 4), |wall| *wall);
+
+        assert!(visible.contains(&Cell::new(4, 3)));
+        assert!(!visible.contains(&Cell::new(4, 2)));
+        assert!(!visible.contains(&Cell::new(4, 1)));
+        assert!(visible.contains(&Cell::new(1, 1)));
+        assert!(visible.contains(&Cell::new(7, 1)));
+    }
+
+    #[test]
+    fn test_symmetry() {
+        let map = parse(&[
+            "##########",
+            "#....#...#",
+            "#.##.....#",
+            "#....#.#.#",
+            "#.#......#",
+            "#...##...#",
+            "##########",
+        ]);
+        let fov = SquareFov {
+            light_walls: false,
+            ..Default::default()
+        };
+        let floors: Vec<Cell> = map
+            .iter()
+            .filter(|(_, wall)| !**wall)
+            .map(|(cell, _)| cell)
+            .collect();
+        for a in floors.iter() {
+            let from_a = fov.visible_in_storage(&map, *a, |wall| *wall);
+            for b in from_a.iter() {
+                let from_b = fov.visible_in_storage(&map, *b, |wall| *wall);
+                assert!(from_b.contains(a), "{} sees {} but not back", a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn test_radius_and_metric() {
+        let open = |_: Cell| false;
+        for (metric, count) in [
+            (SquareMetric::Manhattan, 25),
+            (SquareMetric::Chebyshev, 49),
+            (SquareMetric::Euclidean, 29),
+        ] {
+            let fov = SquareFov {
+                metric,
+                ..Default::default()
+            };
+            let visible = fov.visible_set(Cell::new(10, -4), 3, open);
+            let expected: HashSet<Cell> = Cell::new(10, -4).square_range(3, metric).collect();
+            assert_eq!(visible.len(), count);
+            assert_eq!(visible, expected);
+        }
+    }
+
+    #[test]
+    fn test_bitmask() {
+        let mut visible = Grid::init(5, 5, false);
+        let fov = SquareFov {
+            radius: Some(1),
+            metric: SquareMetric::Chebyshev,
+            ..Default::default()
+        };
+        fov.compute_into(Cell::new(0, 0), |_| false, &mut visible);
+
+        assert_eq!(visible.iter().filter(|v| **v).count(), 4);
+        assert_eq!(GridStorage::get(&visible, Cell::new(1, 1)), Some(&true));
+    }
+
+    #[test]
+    fn test_unbounded_into_storage() {
+        // Without a radius the edges of the bitmask end the scan of an open map
+        let mut visible = Grid::init(6, 6, false);
+        SquareFov::default().compute_into(Cell::new(2, 2), |_| false, &mut visible);
+        assert!(visible.iter().all(|v| *v));
+    }
+
+    #[test]
+    fn test_large_unbounded_storage() {
+        // Every row of an open map is scanned without recursing, however far the view reaches
+        let map = SquareStorage::new_uniform(400, 400, false);
+        let visible =
+            SquareFov::default().visible_in_storage(&map, Cell::new(200, 200), |wall| *wall);
+        assert_eq!(visible.len(), 400 * 400);
+    }
+}
@@ -0,0 +1,236 @@
+use glam::Vec2;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "bevy_reflect")]
+use bevy::prelude::Reflect;
+
+use crate::cell::Cell;
+
+/// Which rows of a staggered isometric map are shoved right by half a tile
+#[derive(Default, Eq, Hash, PartialEq, Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bevy_reflect", derive(Reflect))]
+pub enum IsometricStagger {
+    /// Odd rows are shoved right
+    #[default]
+    OddRows,
+    /// Even rows are shoved right
+    EvenRows,
+}
+
+/// How cells are projected onto the screen by an [`IsometricLayout`]
+#[derive(Default, Eq, Hash, PartialEq, Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bevy_reflect", derive(Reflect))]
+pub enum IsometricProjection {
+    /// The map forms one large diamond. Increasing `x` moves up and right, increasing `y` moves up and left
+    #[default]
+    Diamond,
+    /// The map forms a rectangle. Each row is half a tile higher than the last and every other row is shoved right
+    /// by half a tile
+    Staggered(IsometricStagger),
+}
+
+/// Isometric layout. The bridge between world/pixel space and [`Cell`] coordinates for maps of diamond shaped tiles.
+///
+/// The `y` axis of world space points up.
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+// Reflected as an opaque value since bevy does not reflect the version of glam used for the positions
+#[cfg_attr(
+    feature = "bevy_reflect",
+    derive(Reflect),
+    reflect_value(PartialEq, Debug)
+)]
+pub struct IsometricLayout {
+    /// The world space position of the center of [`Cell::ZERO`]
+    pub origin: Vec2,
+    /// The world space width and height of a single diamond tile
+    pub tile_size: Vec2,
+    /// How cells are placed on the screen
+    pub projection: IsometricProjection,
+}
+
+impl IsometricLayout {
+    /// Returns the world position of the center of the given cell
+    pub fn cell_to_world_pos(&self, cell: Cell) -> Vec2 {
+        let half = self.tile_size / 2.0;
+        let local = match self.projection {
+            IsometricProjection::Diamond => Vec2::new(
+                (cell.x - cell.y) as f32 * half.x,
+                (cell.x + cell.y) as f32 * half.y,
+            ),
+            IsometricProjection::Staggered(stagger) => {
+                let shoved = match stagger {
+                    IsometricStagger::OddRows => cell.y & 1 == 1,
+                    IsometricStagger::EvenRows => cell.y & 1 == 0,
+                };
+                Vec2::new(
+                    cell.x as f32 * self.tile_size.x + if shoved { half.x } else { 0.0 },
+                    cell.y as f32 * half.y,
+                )
+            }
+        };
+        local + self.origin
+    }
+
+    /// Returns the cell whose diamond contains the given world position
+    pub fn world_pos_to_cell(&self, pos: Vec2) -> Cell {
+        let half = self.tile_size / 2.0;
+        let mut local = pos - self.origin;
+        // Even row staggering is odd row staggering moved right by half a tile
+        if self.projection == IsometricProjection::Staggered(IsometricStagger::EvenRows) {
+            local.x -= half.x;
+        }
+
+        // Diamond tiles become unit squares centered on each cell in diamond coordinates
+        let a = local.x / half.x;
+        let b = local.y / half.y;
+        let x = ((a + b) / 2.0 + 0.5).floor() as i32;
+        let y = ((b - a) / 2.0 + 0.5).floor() as i32;
+
+        match self.projection {
+            IsometricProjection::Diamond => Cell::new(x, y),
+            IsometricProjection::Staggered(stagger) => {
+                let row = x + y;
+                let col = (x - y - (row & 1)) / 2;
+                match stagger {
+                    IsometricStagger::OddRows => Cell::new(col, row),
+                    IsometricStagger::EvenRows => Cell::new(col + (row & 1), row),
+                }
+            }
+        }
+    }
+
+    /// Returns the four world space corners of the given cell's diamond, counter clockwise starting from the bottom
+    pub fn cell_corners(&self, cell: Cell) -> [Vec2; 4] {
+        let center = self.cell_to_world_pos(cell);
+        let half = self.tile_size / 2.0;
+        [
+            center - Vec2::new(0.0, half.y),
+            center + Vec2::new(half.x, 0.0),
+            center + Vec2::new(0.0, half.y),
+            center - Vec2::new(half.x, 0.0),
+        ]
+    }
+}
+
+impl Default for IsometricLayout {
+    fn default() -> Self {
+        Self {
+            origin: Vec2::ZERO,
+            tile_size: Vec2::new(2.0, 1.0),
+            projection: IsometricProjection::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::Vec2;
+
+    use crate::cell::{square::SquareMetric, Cell};
+
+    use super::{IsometricLayout, IsometricProjection, IsometricStagger};
+
+    const PROJECTIONS: [IsometricProjection; 3] = [
+        IsometricProjection::Diamond,
+        IsometricProjection::Staggered(IsometricStagger::OddRows),
+        IsometricProjection::Staggered(IsometricStagger::EvenRows),
+    ];
+
+    #[test]
+    fn test_diamond_positions() {
+        let layout = IsometricLayout {
+            origin: Vec2::new(100.0, 50.0),
+            tile_size: Vec2::new(64.0, 32.0),
+            projection: IsometricProjection::Diamond,
+        };
+
+        assert_eq!(layout.cell_to_world_pos(Cell::ZERO), Vec2::new(100.0, 50.0));
+        assert_eq!(layout.cell_to_world_pos(Cell::X), Vec2::new(132.0, 66.0));
+        assert_eq!(layout.cell_to_world_pos(Cell::Y), Vec2::new(68.0, 66.0));
+        assert_eq!(
+            layout.cell_corners(Cell::ZERO),
+            [
+                Vec2::new(100.0, 34.0),
+                Vec2::new(132.0, 50.0),
+                Vec2::new(100.0, 66.0),
+                Vec2::new(68.0, 50.0)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_staggered_positions() {
+        let mut layout = IsometricLayout {
+            tile_size: Vec2::new(64.0, 32.0),
+            projection: IsometricProjection::Staggered(IsometricStagger::OddRows),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            layout.cell_to_world_pos(Cell::new(1, 0)),
+            Vec2::new(64.0, 0.0)
+        );
+        assert_eq!(
+            layout.cell_to_world_pos(Cell::new(1, 1)),
+            Vec2::new(96.0, 16.0)
+        );
+        assert_eq!(
+            layout.cell_to_world_pos(Cell::new(0, -1)),
+            Vec2::new(32.0, -16.0)
+        );
+
+        layout.projection = IsometricProjection::Staggered(IsometricStagger::EvenRows);
+        assert_eq!(
+            layout.cell_to_world_pos(Cell::new(1, 0)),
+            Vec2::new(96.0, 0.0)
+        );
+        assert_eq!(
+            layout.cell_to_world_pos(Cell::new(1, 1)),
+            Vec2::new(64.0, 16.0)
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for projection in PROJECTIONS {
+            let layout = IsometricLayout {
+                origin: Vec2::new(-10.0, 3.0),
+                tile_size: Vec2::new(64.0, 32.0),
+                projection,
+            };
+            for cell in Cell::new(1, -1).square_range(5, SquareMetric::Chebyshev) {
+                assert_eq!(
+                    layout.world_pos_to_cell(layout.cell_to_world_pos(cell)),
+                    cell
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_picking_inside_diamond() {
+        for projection in PROJECTIONS {
+            let layout = IsometricLayout {
+                tile_size: Vec2::new(64.0, 32.0),
+                projection,
+                ..Default::default()
+            };
+            for cell in Cell::new(-2, 3).square_range(4, SquareMetric::Chebyshev) {
+                let center = layout.cell_to_world_pos(cell);
+                // Points just inside each corner of the diamond belong to the cell
+                for corner in layout.cell_corners(cell) {
+                    let inside = center + (corner - center) * 0.95;
+                    assert_eq!(layout.world_pos_to_cell(inside), cell);
+                }
+                // Points inside the bounding box but outside the diamond belong to a neighbor
+                let outside = center + Vec2::new(30.0, 14.0);
+                assert_ne!(layout.world_pos_to_cell(outside), cell);
+            }
+        }
+    }
+}
@@ -0,0 +1,4 @@
+#[cfg(feature = "square")]
+pub mod isometric;
+#[cfg(feature = "square")]
+pub mod square;
@@ -0,0 +1,220 @@
+use glam::Vec2;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "bevy_reflect")]
+use bevy::prelude::Reflect;
+
+use crate::cell::Cell;
+
+/// The point of a cell that is placed on the layout origin
+#[derive(Default, Eq, Hash, PartialEq, Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bevy_reflect", derive(Reflect))]
+pub enum SquareAnchor {
+    /// [`Cell::ZERO`] is centered on the origin
+    #[default]
+    Center,
+    /// The corner of [`Cell::ZERO`] closest to the negative axes is placed on the origin, so the cell covers the area
+    /// between the origin and the origin plus `cell_size`
+    Corner,
+}
+
+/// Square layout. The bridge between world/pixel space and square [`Cell`] coordinates, the square equivalent of
+/// [`HexLayout`](hexx::HexLayout).
+///
+/// By default the `x` axis points right and the `y` axis points up. Either axis can be inverted using `invert_x` and
+/// `invert_y`.
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+// Reflected as an opaque value since bevy does not reflect the version of glam used for the positions
+#[cfg_attr(
+    feature = "bevy_reflect",
+    derive(Reflect),
+    reflect_value(PartialEq, Debug)
+)]
+pub struct SquareLayout {
+    /// The world space position of [`Cell::ZERO`], see `anchor`
+    pub origin: Vec2,
+    /// The world space size of a single cell
+    pub cell_size: Vec2,
+    /// Which point of [`Cell::ZERO`] is placed on the origin
+    pub anchor: SquareAnchor,
+    /// If set to `true`, the `Cell` `x` axis will be inverted
+    pub invert_x: bool,
+    /// If set to `true`, the `Cell` `y` axis will be inverted
+    pub invert_y: bool,
+}
+
+impl SquareLayout {
+    /// Returns the world position of the center of the given cell
+    pub fn cell_to_world_pos(&self, cell: Cell) -> Vec2 {
+        self.fract_cell_to_world_pos(Vec2::new(cell.x as f32, cell.y as f32))
+    }
+
+    /// Returns the world position of fractional cell coordinates, where whole numbers are the center of a cell
+    pub fn fract_cell_to_world_pos(&self, cell: Vec2) -> Vec2 {
+        (cell + self.anchor_offset()) * self.cell_size * self.axis_scale() + self.origin
+    }
+
+    /// Returns the cell containing the given world position
+    pub fn world_pos_to_cell(&self, pos: Vec2) -> Cell {
+        let fract = self.world_pos_to_fract_cell(pos) + Vec2::splat(0.5);
+        Cell::new(fract.x.floor() as i32, fract.y.floor() as i32)
+    }
+
+    /// Returns the fractional cell coordinates of the given world position, where whole numbers are the center of a
+    /// cell
+    pub fn world_pos_to_fract_cell(&self, pos: Vec2) -> Vec2 {
+        (pos - self.origin) * self.axis_scale() / self.cell_size - self.anchor_offset()
+    }
+
+    /// Returns the four world space corners of the given cell, counter clockwise starting from the corner closest to
+    /// the negative `Cell` axes
+    pub fn cell_corners(&self, cell: Cell) -> [Vec2; 4] {
+        let center = self.cell_to_world_pos(cell);
+        let half = self.cell_size * self.axis_scale() / 2.0;
+        [
+            center + Vec2::new(-half.x, -half.y),
+            center + Vec2::new(half.x, -half.y),
+            center + Vec2::new(half.x, half.y),
+            center + Vec2::new(-half.x, half.y),
+        ]
+    }
+
+    /// Returns the world space bounding rectangle of the given cell as its `(min, max)` corners
+    pub fn cell_rect(&self, cell: Cell) -> (Vec2, Vec2) {
+        let center = self.cell_to_world_pos(cell);
+        let half = (self.cell_size / 2.0).abs();
+        (center - half, center + half)
+    }
+
+    /// Returns a signum axis coefficient, allowing for inverted axis
+    fn axis_scale(&self) -> Vec2 {
+        let x = if self.invert_x { -1.0 } else { 1.0 };
+        let y = if self.invert_y { -1.0 } else { 1.0 };
+        Vec2::new(x, y)
+    }
+
+    /// The offset in cells from the origin to the center of [`Cell::ZERO`]
+    fn anchor_offset(&self) -> Vec2 {
+        match self.anchor {
+            SquareAnchor::Center => Vec2::ZERO,
+            SquareAnchor::Corner => Vec2::splat(0.5),
+        }
+    }
+}
+
+impl Default for SquareLayout {
+    fn default() -> Self {
+        Self {
+            origin: Vec2::ZERO,
+            cell_size: Vec2::ONE,
+            anchor: SquareAnchor::default(),
+            invert_x: false,
+            invert_y: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::Vec2;
+
+    use crate::cell::Cell;
+
+    use super::{SquareAnchor, SquareLayout};
+
+    #[test]
+    fn test_center_anchor() {
+        let layout = SquareLayout {
+            origin: Vec2::new(10.0, 5.0),
+            cell_size: Vec2::new(2.0, 4.0),
+            ..Default::default()
+        };
+
+        assert_eq!(layout.cell_to_world_pos(Cell::ZERO), Vec2::new(10.0, 5.0));
+        assert_eq!(
+            layout.cell_to_world_pos(Cell::new(-2, 1)),
+            Vec2::new(6.0, 9.0)
+        );
+        assert_eq!(layout.world_pos_to_cell(Vec2::new(10.9, 6.9)), Cell::ZERO);
+        assert_eq!(
+            layout.world_pos_to_cell(Vec2::new(8.9, 2.9)),
+            Cell::new(-1, -1)
+        );
+    }
+
+    #[test]
+    fn test_corner_anchor_negative() {
+        let layout = SquareLayout {
+            cell_size: Vec2::splat(16.0),
+            anchor: SquareAnchor::Corner,
+            ..Default::default()
+        };
+
+        assert_eq!(layout.cell_to_world_pos(Cell::ZERO), Vec2::splat(8.0));
+        assert_eq!(layout.world_pos_to_cell(Vec2::new(0.5, 15.5)), Cell::ZERO);
+        assert_eq!(
+            layout.world_pos_to_cell(Vec2::new(-0.5, -0.5)),
+            Cell::NEG_ONE
+        );
+        assert_eq!(
+            layout.world_pos_to_cell(Vec2::new(-16.5, 3.0)),
+            Cell::new(-2, 0)
+        );
+        assert_eq!(
+            layout.cell_rect(Cell::new(-1, 2)),
+            (Vec2::new(-16.0, 32.0), Vec2::new(0.0, 48.0))
+        );
+    }
+
+    #[test]
+    fn test_inverted_axis() {
+        let layout = SquareLayout {
+            cell_size: Vec2::splat(2.0),
+            anchor: SquareAnchor::Corner,
+            invert_y: true,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            layout.cell_to_world_pos(Cell::new(1, 1)),
+            Vec2::new(3.0, -3.0)
+        );
+        assert_eq!(
+            layout.world_pos_to_cell(Vec2::new(3.0, -3.0)),
+            Cell::new(1, 1)
+        );
+        assert_eq!(
+            layout.cell_rect(Cell::new(1, 1)),
+            (Vec2::new(2.0, -4.0), Vec2::new(4.0, -2.0))
+        );
+        assert_eq!(
+            layout.cell_corners(Cell::ZERO),
+            [
+                Vec2::new(0.0, 0.0),
+                Vec2::new(2.0, 0.0),
+                Vec2::new(2.0, -2.0),
+                Vec2::new(0.0, -2.0)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let layout = SquareLayout {
+            origin: Vec2::new(-3.0, 7.5),
+            cell_size: Vec2::new(1.5, 0.5),
+            invert_x: true,
+            ..Default::default()
+        };
+        for cell in Cell::new(-4, 2).square_range(6, Default::default()) {
+            assert_eq!(
+                layout.world_pos_to_cell(layout.cell_to_world_pos(cell)),
+                cell
+            );
+        }
+    }
+}
@@ -1,4 +1,11 @@
 pub mod cell;
+pub mod error;
+pub mod fov;
+pub mod layout;
+#[cfg(any(feature = "square", feature = "hex"))]
+pub mod pathfinding;
+#[cfg(any(feature = "square", feature = "hex"))]
+pub mod region;
 pub mod storage;
 
 #[cfg(feature = "hex")]
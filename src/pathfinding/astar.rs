@@ -0,0 +1,393 @@
+use std::collections::{BinaryHeap, HashMap};
+
+use crate::{cell::topology::Topology, cell::Cell, storage::GridStorage};
+
+use super::ScoredCell;
+
+/// A path found by [`AStar`]
+#[derive(Default, Clone, PartialEq, Debug)]
+pub struct PathResult {
+    /// Every cell on the path, starting with the start cell and ending with the goal cell
+    pub path: Vec<Cell>,
+    /// The total cost of walking the path
+    pub cost: f32,
+    /// How many cells were expanded while searching
+    pub expanded: usize,
+}
+
+#[derive(Copy, Clone, Debug)]
+struct Visit {
+    cost: f32,
+    parent: Option<Cell>,
+    closed: bool,
+}
+
+/// A* search over [`Cell`]s.
+///
+/// The search keeps its buffers between calls so a single [`AStar`] can be reused to search repeatedly without
+/// allocating once the buffers have grown. Combine with [`AStar::find_path_into`] or
+/// [`AStar::find_path_in_storage_into`] to also reuse the path buffer.
+///
+/// A search is unbounded by default. When `passable` does not describe a finite map an unreachable goal makes the
+/// search run forever, so set a limit with [`AStar::with_max_cost`] or [`AStar::with_max_expanded`]
+#[derive(Default, Clone, Debug)]
+pub struct AStar {
+    open: BinaryHeap<ScoredCell>,
+    visited: HashMap<Cell, Visit>,
+    max_cost: Option<f32>,
+    max_expanded: Option<usize>,
+    min_step_cost: Option<f32>,
+}
+
+impl AStar {
+    pub fn new() -> AStar {
+        AStar::default()
+    }
+
+    /// Never walks a path costing more than `max_cost`. A goal that costs more to reach is treated as unreachable
+    pub fn with_max_cost(mut self, max_cost: f32) -> AStar {
+        self.max_cost = Some(max_cost);
+        self
+    }
+
+    /// Gives up once `max_expanded` cells have been expanded without reaching the goal
+    pub fn with_max_expanded(mut self, max_expanded: usize) -> AStar {
+        self.max_expanded = Some(max_expanded);
+        self
+    }
+
+    /// Sets the cheapest cost of a single step, which scales the heuristic. Defaults to `1.0`. Lower it when some
+    /// steps cost less, such as roads, or the path found may not be the cheapest one
+    pub fn with_min_step_cost(mut self, min_step_cost: f32) -> AStar {
+        self.min_step_cost = Some(min_step_cost);
+        self
+    }
+
+    /// Finds the cheapest path from `start` to `goal`.
+    ///
+    /// - `passable` returns whether a cell can be entered. The start cell is always allowed
+    /// - `cost` returns the cost of stepping from a cell into its neighbor
+    ///
+    /// The heuristic is the [`Topology::distance`] to the goal scaled by the cheapest step cost, so every step must
+    /// cost at least [`AStar::with_min_step_cost`] for the path to be the cheapest one. Returns None if the goal cannot be reached or a limit set with [`AStar::with_max_cost`] or
+    /// [`AStar::with_max_expanded`] is hit
+    pub fn find_path(
+        &mut self,
+        start: Cell,
+        goal: Cell,
+        topology: Topology,
+        passable: impl FnMut(Cell) -> bool,
+        cost: impl FnMut(Cell, Cell) -> f32,
+    ) -> Option<PathResult> {
+        let mut result = PathResult::default();
+        self.find_path_into(start, goal, topology, passable, cost, &mut result)
+            .then_some(result)
+    }
+
+    /// Finds the cheapest path from `start` to `goal` reading the cost of entering each cell from a storage.
+    ///
+    /// `cost` returns the cost of entering a cell with the given data, or None if it is impassable. Cells outside the
+    /// storage are impassable. Each neighbor is looked up once per step. Costs below [`AStar::with_min_step_cost`]
+    /// make the heuristic overestimate, so the path found may not be the cheapest one. See [`AStar::find_path`]
+    pub fn find_path_in_storage<T>(
+        &mut self,
+        storage: &impl GridStorage<T>,
+        start: Cell,
+        goal: Cell,
+        topology: Topology,
+        cost: impl Fn(&T) -> Option<f32>,
+    ) -> Option<PathResult> {
+        let mut result = PathResult::default();
+        self.find_path_in_storage_into(storage, start, goal, topology, cost, &mut result)
+            .then_some(result)
+    }
+
+    /// Finds the cheapest path from `start` to `goal` reading costs from a storage, writing it into `result` and
+    /// reusing its path buffer.
+    ///
+    /// Returns whether a path was found. `result` is left empty when no path exists. See
+    /// [`AStar::find_path_in_storage`]
+    pub fn find_path_in_storage_into<T>(
+        &mut self,
+        storage: &impl GridStorage<T>,
+        start: Cell,
+        goal: Cell,
+        topology: Topology,
+        cost: impl Fn(&T) -> Option<f32>,
+        result: &mut PathResult,
+    ) -> bool {
+        self.search(
+            start,
+            goal,
+            topology,
+            |_, to| storage.get(to).and_then(&cost),
+            result,
+        )
+    }
+
+    /// Finds the cheapest path from `start` to `goal`, writing it into `result` and reusing its path buffer.
+    ///
+    /// Returns whether a path was found. `result` is left empty when no path exists. See [`AStar::find_path`]
+    pub fn find_path_into(
+        &mut self,
+        start: Cell,
+        goal: Cell,
+        topology: Topology,
+        mut passable: impl FnMut(Cell) -> bool,
+        mut cost: impl FnMut(Cell, Cell) -> f32,
+        result: &mut PathResult,
+    ) -> bool {
+        self.search(
+            start,
+            goal,
+            topology,
+            |from, to| passable(to).then(|| cost(from, to)),
+            result,
+        )
+    }
+
+    /// Runs the search with `step` returning the cost of stepping between two cells, or None if the second cell is
+    /// impassable
+    fn search(
+        &mut self,
+        start: Cell,
+        goal: Cell,
+        topology: Topology,
+        mut step: impl FnMut(Cell, Cell) -> Option<f32>,
+        result: &mut PathResult,
+    ) -> bool {
+        let scale = self.min_step_cost.unwrap_or(1.0);
+        self.open.clear();
+        self.visited.clear();
+        result.path.clear();
+        result.cost = 0.0;
+        result.expanded = 0;
+
+        self.visited.insert(
+            start,
+            Visit {
+                cost: 0.0,
+                parent: None,
+                closed: false,
+            },
+        );
+        self.open.push(ScoredCell {
+            score: topology.distance(start, goal) as f32 * scale,
+            cell: start,
+        });
+
+        while let Some(ScoredCell { cell, .. }) = self.open.pop() {
+            let visit = self
+                .visited
+                .get_mut(&cell)
+                .expect("Open cells are always visited");
+            if visit.closed {
+                continue;
+            }
+            if self
+                .max_expanded
+                .is_some_and(|max_expanded| result.expanded >= max_expanded)
+            {
+                break;
+            }
+            visit.closed = true;
+            let cell_cost = visit.cost;
+            result.expanded += 1;
+
+            if cell == goal {
+                result.cost = cell_cost;
+                let mut current = Some(goal);
+                while let Some(cell) = current {
+                    result.path.push(cell);
+                    current = self.visited[&cell].parent;
+                }
+                result.path.reverse();
+                return true;
+            }
+
+            for neighbor in topology.neighbors(cell) {
+                let Some(step_cost) = step(cell, neighbor) else {
+                    continue;
+                };
+                let neighbor_cost = cell_cost + step_cost;
+                if self
+                    .max_cost
+                    .is_some_and(|max_cost| neighbor_cost > max_cost)
+                {
+                    continue;
+                }
+                if let Some(visit) = self.visited.get(&neighbor) {
+                    if visit.closed || visit.cost <= neighbor_cost {
+                        continue;
+                    }
+                }
+                self.visited.insert(
+                    neighbor,
+                    Visit {
+                        cost: neighbor_cost,
+                        parent: Some(cell),
+                        closed: false,
+                    },
+                );
+                self.open.push(ScoredCell {
+                    score: neighbor_cost + topology.distance(neighbor, goal) as f32 * scale,
+                    cell: neighbor,
+                });
+            }
+        }
+
+        false
+    }
+}
+
+#[cfg(all(test, feature = "square"))]
+mod tests {
+    use crate::cell::{square::SquareConnectivity, topology::Topology, Cell};
+    use crate::storage::square::SquareStorage;
+
+    use super::{AStar, PathResult};
+
+    #[test]
+    fn test_straight_path() {
+        let mut astar = AStar::new();
+        let result = astar
+            .find_path(
+                Cell::ZERO,
+                Cell::new(4, 0),
+                Topology::Square(SquareConnectivity::Four),
+                |_| true,
+                |_, _| 1.0,
+            )
+            .unwrap();
+
+        assert_eq!(result.cost, 4.0);
+        assert_eq!(
+            result.path,
+            (0..=4).map(|x| Cell::new(x, 0)).collect::<Vec<_>>()
+        );
+        assert_eq!(result.expanded, 5);
+    }
+
+    #[test]
+    fn test_path_around_wall() {
+        let wall = |cell: Cell| cell.x == 2 && cell.y < 3;
+        let mut astar = AStar::new();
+        let mut result = PathResult::default();
+        for connectivity in [SquareConnectivity::Four, SquareConnectivity::Eight] {
+            let found = astar.find_path_into(
+                Cell::ZERO,
+                Cell::new(4, 0),
+                Topology::Square(connectivity),
+                |cell| !wall(cell),
+                |_, _| 1.0,
+                &mut result,
+            );
+            assert!(found);
+            assert!(result.path.iter().all(|cell| !wall(*cell)));
+            assert_eq!(result.cost, (result.path.len() - 1) as f32);
+        }
+        assert_eq!(result.cost, 6.0);
+    }
+
+    #[test]
+    fn test_unreachable() {
+        let mut astar = AStar::new();
+        let result = astar.find_path(
+            Cell::ZERO,
+            Cell::new(3, 3),
+            Topology::Square(SquareConnectivity::Four),
+            |cell| cell.manhattan_distance(Cell::ZERO) < 3,
+            |_, _| 1.0,
+        );
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn test_enclosed_goal_limits() {
+        // The goal is walled in on an otherwise endless open map
+        let goal = Cell::new(5, 5);
+        let passable = |cell: Cell| cell == goal || cell.chebyshev_distance(goal) > 1;
+        let topology = Topology::Square(SquareConnectivity::Eight);
+
+        let mut astar = AStar::new().with_max_expanded(500);
+        assert!(astar
+            .find_path(Cell::ZERO, goal, topology, passable, |_, _| 1.0)
+            .is_none());
+
+        let mut astar = AStar::new().with_max_cost(20.0);
+        assert!(astar
+            .find_path(Cell::ZERO, goal, topology, passable, |_, _| 1.0)
+            .is_none());
+
+        // The limits do not get in the way of a reachable goal
+        let result = astar
+            .find_path(Cell::ZERO, Cell::new(3, 0), topology, passable, |_, _| 1.0)
+            .unwrap();
+        assert_eq!(result.cost, 3.0);
+    }
+
+    #[test]
+    fn test_storage_costs() {
+        // A cheap road along the top row and an impassable wall in the middle column
+        let map = SquareStorage::new_from_vec(vec![
+            vec![Some(1.0), Some(1.0), Some(1.0), Some(1.0), Some(1.0)],
+            vec![Some(5.0), Some(5.0), None, Some(5.0), Some(5.0)],
+            vec![Some(5.0), Some(5.0), None, Some(5.0), Some(5.0)],
+        ]);
+        let mut astar = AStar::new();
+        let result = astar
+            .find_path_in_storage(
+                &map,
+                Cell::new(0, 2),
+                Cell::new(4, 2),
+                Topology::Square(SquareConnectivity::Four),
+                |cost| *cost,
+            )
+            .unwrap();
+
+        assert_eq!(result.cost, 5.0 + 1.0 + 4.0 + 10.0);
+        assert_eq!(result.path.first(), Some(&Cell::new(0, 2)));
+        assert_eq!(result.path.last(), Some(&Cell::new(4, 2)));
+    }
+
+    #[test]
+    fn test_cheap_road() {
+        // A road cheaper than a regular step along the top row
+        let map = SquareStorage::new_from_vec(vec![
+            vec![0.1, 0.1, 0.1, 0.1, 0.1],
+            vec![1.0, 1.0, 1.0, 1.0, 1.0],
+            vec![1.0, 1.0, 1.0, 1.0, 1.0],
+        ]);
+        let mut astar = AStar::new().with_min_step_cost(0.1);
+        let mut result = PathResult::default();
+        for _ in 0..2 {
+            assert!(astar.find_path_in_storage_into(
+                &map,
+                Cell::new(0, 2),
+                Cell::new(4, 2),
+                Topology::Square(SquareConnectivity::Four),
+                |cost| Some(*cost),
+                &mut result,
+            ));
+            assert!((result.cost - 3.5).abs() < 0.001);
+            assert!(result.path.contains(&Cell::new(2, 0)));
+        }
+    }
+
+    #[cfg(feature = "hex")]
+    #[test]
+    fn test_hex_path() {
+        let mut astar = AStar::new();
+        let result = astar
+            .find_path(
+                Cell::ZERO,
+                Cell::new(3, -3),
+                Topology::Hex,
+                |_| true,
+                |_, _| 1.0,
+            )
+            .unwrap();
+        assert_eq!(result.cost, 3.0);
+        assert_eq!(result.path.len(), 4);
+    }
+}
@@ -0,0 +1,382 @@
+use std::collections::{BinaryHeap, HashMap, HashSet};
+
+use crate::{cell::topology::Topology, cell::Cell, storage::GridStorage};
+
+use super::ScoredCell;
+
+/// A map of the cost from every cell to its nearest goal, also known as a distance field.
+///
+/// The costs are written into any [`GridStorage<f32>`] such as a [`Grid<f32>`](crate::storage::grid::Grid) or a
+/// storage of the same shape as the map. Unreachable cells hold [`f32::INFINITY`].
+///
+/// Following [`DijkstraMap::next_step`] from any cell rolls downhill to the nearest goal.
+#[derive(Clone, Debug)]
+pub struct DijkstraMap<S> {
+    pub costs: S,
+    pub topology: Topology,
+    goals: HashMap<Cell, f32>,
+    open: BinaryHeap<ScoredCell>,
+}
+
+impl<S: GridStorage<f32>> DijkstraMap<S> {
+    /// Construct a new [`DijkstraMap`] writing into the given storage. Every cell starts unreachable
+    pub fn new(mut costs: S, topology: Topology) -> DijkstraMap<S> {
+        for (_, cost) in costs.iter_mut() {
+            *cost = f32::INFINITY;
+        }
+        DijkstraMap {
+            costs,
+            topology,
+            goals: HashMap::new(),
+            open: BinaryHeap::new(),
+        }
+    }
+
+    /// The cost from the given cell to its nearest goal. Returns None if the cell is outside the map
+    pub fn get(&self, cell: Cell) -> Option<f32> {
+        self.costs.get(cell).copied()
+    }
+
+    /// Computes the cost from every cell to the nearest of the given goals.
+    ///
+    /// `cost` returns the cost of stepping from a cell into its neighbor, or None if the step is not possible. Step
+    /// costs must be positive
+    pub fn compute(
+        &mut self,
+        goals: impl IntoIterator<Item = Cell>,
+        cost: impl FnMut(Cell, Cell) -> Option<f32>,
+    ) {
+        self.compute_weighted(goals.into_iter().map(|goal| (goal, 0.0)), cost);
+    }
+
+    /// Computes the cost from every cell to the nearest of the given goals, where each goal starts at the given
+    /// value instead of zero. Lower values make a goal more desirable. See [`DijkstraMap::compute`]
+    pub fn compute_weighted(
+        &mut self,
+        goals: impl IntoIterator<Item = (Cell, f32)>,
+        cost: impl FnMut(Cell, Cell) -> Option<f32>,
+    ) {
+        for (_, cost) in self.costs.iter_mut() {
+            *cost = f32::INFINITY;
+        }
+        self.goals.clear();
+        self.open.clear();
+        for (goal, value) in goals {
+            let Some(current) = self.costs.get_mut(goal) else {
+                continue;
+            };
+            *current = current.min(value);
+            self.goals
+                .entry(goal)
+                .and_modify(|goal_value| *goal_value = goal_value.min(value))
+                .or_insert(value);
+            self.open.push(ScoredCell {
+                score: value,
+                cell: goal,
+            });
+        }
+        self.propagate(cost, |_| {});
+    }
+
+    /// Creates a map for fleeing from the goals of this map.
+    ///
+    /// Every reachable cost is multiplied by `-coefficient` and the map is rescanned so that following
+    /// [`DijkstraMap::next_step`] leads away from the goals while still avoiding dead ends. A coefficient around `1.2`
+    /// gives a good balance between fleeing far and fleeing smartly
+    pub fn fleeing(
+        &self,
+        coefficient: f32,
+        cost: impl FnMut(Cell, Cell) -> Option<f32>,
+    ) -> DijkstraMap<S>
+    where
+        S: Clone,
+    {
+        let goals: Vec<(Cell, f32)> = self
+            .costs
+            .iter()
+            .filter(|(_, cost)| cost.is_finite())
+            .map(|(cell, cost)| (cell, cost * -coefficient))
+            .collect();
+        let mut map = DijkstraMap::new(self.costs.clone(), self.topology);
+        map.compute_weighted(goals, cost);
+        map
+    }
+
+    /// Rolls downhill from the given cell, returning the neighbor with the lowest cost if it is lower than the cost
+    /// of the given cell. Returns None when standing on a goal or a local minimum
+    pub fn next_step(&self, from: Cell) -> Option<Cell> {
+        let mut best = (from, self.get(from)?);
+        for neighbor in self.topology.neighbors(from) {
+            let Some(cost) = self.get(neighbor) else {
+                continue;
+            };
+            if cost < best.1 {
+                best = (neighbor, cost);
+            }
+        }
+        (best.0 != from).then_some(best.0)
+    }
+
+    /// Recomputes the map after the step costs into or out of the given cells changed, without rescanning the whole
+    /// map.
+    ///
+    /// The changed cells are reset along with every cell that can no longer reach a goal as cheaply without passing
+    /// through a reset cell. The reset cells are then filled back in from their unaffected surroundings. Returns every
+    /// cell whose cost was recomputed. See [`DijkstraMap::compute`]
+    pub fn update(
+        &mut self,
+        changed: impl IntoIterator<Item = Cell>,
+        mut cost: impl FnMut(Cell, Cell) -> Option<f32>,
+    ) -> HashSet<Cell> {
+        let changed: HashSet<Cell> = changed
+            .into_iter()
+            .filter(|cell| self.costs.contains(*cell))
+            .collect();
+        // Candidates are checked from the lowest cost up, so every cell a candidate could have stepped through is
+        // already known to be reset or kept by the time the candidate is checked
+        let mut candidates: BinaryHeap<ScoredCell> = changed
+            .iter()
+            .filter_map(|cell| {
+                Some(ScoredCell {
+                    score: self.get(*cell)?,
+                    cell: *cell,
+                })
+            })
+            .collect();
+        let mut reset: HashSet<Cell> = HashSet::new();
+        while let Some(ScoredCell { score, cell }) = candidates.pop() {
+            if reset.contains(&cell)
+                || (!changed.contains(&cell) && self.has_support(cell, score, &reset, &mut cost))
+            {
+                continue;
+            }
+            reset.insert(cell);
+            // Step costs are positive, so only a neighbor with a strictly higher cost could have stepped through here
+            for neighbor in self.topology.neighbors(cell) {
+                if let Some(value) = self
+                    .get(neighbor)
+                    .filter(|value| value.is_finite() && *value > score)
+                {
+                    candidates.push(ScoredCell {
+                        score: value,
+                        cell: neighbor,
+                    });
+                }
+            }
+        }
+
+        for cell in reset.iter() {
+            self.costs.set(*cell, f32::INFINITY);
+        }
+        self.open.clear();
+        for (goal, value) in self.goals.iter() {
+            if reset.contains(goal) {
+                self.costs.set(*goal, *value);
+                self.open.push(ScoredCell {
+                    score: *value,
+                    cell: *goal,
+                });
+            }
+        }
+        // Refill the reset cells from their neighbors that kept their costs
+        for cell in reset.iter() {
+            for neighbor in self.topology.neighbors(*cell) {
+                if reset.contains(&neighbor) {
+                    continue;
+                }
+                let Some(value) = self.get(neighbor).filter(|value| value.is_finite()) else {
+                    continue;
+                };
+                self.open.push(ScoredCell {
+                    score: value,
+                    cell: neighbor,
+                });
+            }
+        }
+        let mut recomputed = reset;
+        self.propagate(cost, |cell| {
+            recomputed.insert(cell);
+        });
+        recomputed
+    }
+
+    /// Returns true if the cell is a goal with the given cost, or if stepping from the cell into a neighbor that was
+    /// not reset still gives the given cost
+    fn has_support(
+        &self,
+        cell: Cell,
+        value: f32,
+        reset: &HashSet<Cell>,
+        cost: &mut impl FnMut(Cell, Cell) -> Option<f32>,
+    ) -> bool {
+        if self.goals.get(&cell) == Some(&value) {
+            return true;
+        }
+        self.topology.neighbors(cell).any(|neighbor| {
+            !reset.contains(&neighbor)
+                && self
+                    .get(neighbor)
+                    .zip(cost(cell, neighbor))
+                    .is_some_and(|(neighbor_value, step)| neighbor_value + step == value)
+        })
+    }
+
+    /// Runs Dijkstra's algorithm from every cell in the open set, lowering the cost of any cell that can be reached
+    /// more cheaply. `on_lowered` is called with every cell whose cost was lowered
+    fn propagate(
+        &mut self,
+        mut cost: impl FnMut(Cell, Cell) -> Option<f32>,
+        mut on_lowered: impl FnMut(Cell),
+    ) {
+        while let Some(ScoredCell { score, cell }) = self.open.pop() {
+            if self.get(cell).is_some_and(|current| score > current) {
+                continue;
+            }
+            for neighbor in self.topology.neighbors(cell) {
+                // Costs flow towards the goals, so the step is taken from the neighbor into this cell
+                let Some(step) = cost(neighbor, cell) else {
+                    continue;
+                };
+                let Some(current) = self.costs.get_mut(neighbor) else {
+                    continue;
+                };
+                let candidate = score + step;
+                if candidate < *current {
+                    *current = candidate;
+                    on_lowered(neighbor);
+                    self.open.push(ScoredCell {
+                        score: candidate,
+                        cell: neighbor,
+                    });
+                }
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "square"))]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::cell::{square::SquareConnectivity, topology::Topology, Cell};
+    use crate::storage::{grid::Grid, GridStorage};
+
+    use super::DijkstraMap;
+
+    const FOUR: Topology = Topology::Square(SquareConnectivity::Four);
+
+    fn open_cost(walls: &[Cell]) -> impl Fn(Cell, Cell) -> Option<f32> + '_ {
+        move |from, to| (!walls.contains(&from) && !walls.contains(&to)).then_some(1.0)
+    }
+
+    #[test]
+    fn test_multiple_goals() {
+        let mut map = DijkstraMap::new(Grid::init(5, 7, 0.0), FOUR);
+        map.compute([Cell::new(0, 0), Cell::new(6, 4)], open_cost(&[]));
+
+        assert_eq!(map.get(Cell::new(0, 0)), Some(0.0));
+        assert_eq!(map.get(Cell::new(6, 4)), Some(0.0));
+        assert_eq!(map.get(Cell::new(2, 1)), Some(3.0));
+        assert_eq!(map.get(Cell::new(5, 3)), Some(2.0));
+        assert_eq!(map.get(Cell::new(7, 0)), None);
+    }
+
+    #[test]
+    fn test_walls_and_roll_downhill() {
+        let walls: Vec<Cell> = (0..4).map(|y| Cell::new(2, y)).collect();
+        let mut map = DijkstraMap::new(Grid::init(5, 5, 0.0), FOUR);
+        map.compute([Cell::new(4, 0)], open_cost(&walls));
+
+        assert_eq!(map.get(Cell::new(2, 0)), Some(f32::INFINITY));
+        assert_eq!(map.get(Cell::new(0, 0)), Some(12.0));
+
+        let mut cell = Cell::new(0, 0);
+        let mut steps = 0;
+        while let Some(next) = map.next_step(cell) {
+            assert!(!walls.contains(&next));
+            cell = next;
+            steps += 1;
+        }
+        assert_eq!(cell, Cell::new(4, 0));
+        assert_eq!(steps, 12);
+    }
+
+    #[test]
+    fn test_fleeing() {
+        let mut map = DijkstraMap::new(Grid::init(1, 9, 0.0), FOUR);
+        map.compute([Cell::new(3, 0)], open_cost(&[]));
+        let fleeing = map.fleeing(1.2, open_cost(&[]));
+
+        // Fleeing from the goal moves towards the far end of the corridor
+        assert_eq!(fleeing.next_step(Cell::new(3, 0)), Some(Cell::new(4, 0)));
+        assert_eq!(fleeing.next_step(Cell::new(7, 0)), Some(Cell::new(8, 0)));
+        assert!(fleeing.get(Cell::new(8, 0)).unwrap() < fleeing.get(Cell::new(0, 0)).unwrap());
+    }
+
+    #[test]
+    fn test_incremental_update() {
+        let mut walls = vec![Cell::new(3, 1), Cell::new(3, 2), Cell::new(3, 3)];
+        let mut map = DijkstraMap::new(Grid::init(5, 7, 0.0), FOUR);
+        map.compute([Cell::new(0, 2)], open_cost(&walls));
+
+        // Close the gap at the top and open one in the middle
+        walls.push(Cell::new(3, 0));
+        walls.retain(|cell| *cell != Cell::new(3, 2));
+        map.update([Cell::new(3, 0), Cell::new(3, 2)], open_cost(&walls));
+
+        let mut expected = DijkstraMap::new(Grid::init(5, 7, 0.0), FOUR);
+        expected.compute([Cell::new(0, 2)], open_cost(&walls));
+        for (cell, cost) in GridStorage::iter(&expected.costs) {
+            assert_eq!(map.get(cell), Some(*cost), "{}", cell);
+        }
+    }
+
+    #[cfg(feature = "hex")]
+    #[test]
+    fn test_hex_storage() {
+        use hexx::HexOrientation;
+
+        use crate::storage::hex::HexRectangleStorage;
+
+        let storage = HexRectangleStorage::new_uniform(6, 6, 0.0, HexOrientation::Pointy);
+        let mut map = DijkstraMap::new(storage, Topology::Hex);
+        map.compute([Cell::new(0, 0)], open_cost(&[]));
+
+        for (cell, cost) in map.costs.iter() {
+            assert_eq!(
+                *cost,
+                Topology::Hex.distance(Cell::ZERO, cell) as f32,
+                "{}",
+                cell
+            );
+        }
+    }
+
+    #[test]
+    fn test_update_resets_only_dependent_cells() {
+        let goal = Cell::new(0, 0);
+        let mut walls = vec![];
+        let mut map = DijkstraMap::new(Grid::init(32, 32, 0.0), FOUR);
+        map.compute([goal], open_cost(&walls));
+
+        // Every cell behind the wall can still go around it at the same cost
+        walls.push(Cell::new(20, 20));
+        let recomputed = map.update([Cell::new(20, 20)], open_cost(&walls));
+        assert_eq!(recomputed, HashSet::from([Cell::new(20, 20)]));
+
+        // Only the cells along the edge behind the wall next to the goal get more expensive
+        walls.push(Cell::new(1, 0));
+        let recomputed = map.update([Cell::new(1, 0)], open_cost(&walls));
+        assert_eq!(recomputed.len(), 31);
+        assert!(recomputed.iter().all(|cell| cell.y == 0));
+
+        let mut expected = DijkstraMap::new(Grid::init(32, 32, 0.0), FOUR);
+        expected.compute([goal], open_cost(&walls));
+        assert_eq!(map.costs, expected.costs);
+
+        walls.clear();
+        map.update([Cell::new(1, 0), Cell::new(20, 20)], open_cost(&walls));
+        expected.compute([goal], open_cost(&walls));
+        assert_eq!(map.costs, expected.costs);
+    }
+}
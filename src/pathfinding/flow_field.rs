@@ -0,0 +1,508 @@
+use std::collections::{HashMap, HashSet};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "bevy_reflect")]
+use bevy::prelude::Reflect;
+
+use crate::{cell::topology::Topology, cell::Cell, storage::GridStorage};
+
+use super::dijkstra::DijkstraMap;
+
+/// The flow out of a single cell of a [`FlowField`]
+#[derive(Default, Eq, Hash, PartialEq, Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bevy_reflect", derive(Reflect))]
+pub struct Flow {
+    /// The offset to the neighbor to move into. [`Cell::ZERO`] on targets and unreachable cells
+    pub direction: Cell,
+    /// The nearest target if it can be reached in a straight line from this cell. Units standing on this cell can
+    /// steer straight towards it instead of following `direction`, which smooths movement around corners
+    pub line_of_sight: Option<Cell>,
+}
+
+/// A flow field guiding any number of units towards a target region without pathfinding for each unit.
+///
+/// The field is made of an integration field, a [`DijkstraMap`] of the cost to the nearest target, and a flow storage
+/// holding the [`Flow`] out of every cell. Both storages must be the same shape, for example two
+/// [`Grid`](crate::storage::grid::Grid)s or two storages of the same shape as the map.
+///
+/// The field is split into square chunks of cells. When obstacles change only the chunks affected by the change are
+/// regenerated, see [`FlowField::update`]
+#[derive(Clone, Debug)]
+pub struct FlowField<S, F> {
+    pub integration: DijkstraMap<S>,
+    pub flow: F,
+    chunk_size: i32,
+    targets: Vec<Cell>,
+    /// The indices of the targets in each chunk, used to find the nearest target without scanning every target
+    target_chunks: HashMap<Cell, Vec<usize>>,
+    /// The furthest any cell of each chunk is from its nearest target, which bounds how far its line of sight reaches
+    chunk_reach: HashMap<Cell, u32>,
+}
+
+impl<S: GridStorage<f32>, F: GridStorage<Flow>> FlowField<S, F> {
+    /// Construct a new [`FlowField`] writing into the given storages. Chunks default to 16 by 16 cells
+    pub fn new(integration: S, flow: F, topology: Topology) -> FlowField<S, F> {
+        FlowField {
+            integration: DijkstraMap::new(integration, topology),
+            flow,
+            chunk_size: 16,
+            targets: vec![],
+            target_chunks: HashMap::new(),
+            chunk_reach: HashMap::new(),
+        }
+    }
+
+    /// Sets the width and height in cells of the chunks regenerated by [`FlowField::update`]
+    pub fn with_chunk_size(mut self, chunk_size: u32) -> FlowField<S, F> {
+        self.chunk_size = chunk_size.max(1) as i32;
+        self.index_targets();
+        self
+    }
+
+    /// The flow out of the given cell. Returns None if the cell is outside the field
+    pub fn get(&self, cell: Cell) -> Option<&Flow> {
+        self.flow.get(cell)
+    }
+
+    /// The chunk containing the given cell
+    pub fn chunk_of(&self, cell: Cell) -> Cell {
+        Cell::new(
+            cell.x.div_euclid(self.chunk_size),
+            cell.y.div_euclid(self.chunk_size),
+        )
+    }
+
+    /// Builds the whole field flowing towards the given target cells.
+    ///
+    /// `cost` returns the cost of stepping from a cell into its neighbor, or None if the step is not possible. It is
+    /// also used to check line of sight, a straight line is clear when every step along it is possible
+    pub fn build(
+        &mut self,
+        targets: impl IntoIterator<Item = Cell>,
+        mut cost: impl FnMut(Cell, Cell) -> Option<f32>,
+    ) {
+        self.targets = targets.into_iter().collect();
+        self.index_targets();
+        self.integration
+            .compute(self.targets.iter().copied(), &mut cost);
+        let cells: Vec<Cell> = self.flow.iter().map(|(cell, _)| cell).collect();
+        for cell in cells {
+            self.regenerate_cell(cell, &mut cost);
+        }
+    }
+
+    /// Updates the field after the step costs into or out of the given cells changed.
+    ///
+    /// The integration field is updated incrementally, then every chunk containing a changed cell or a cell whose
+    /// integration or neighborhood changed is regenerated. Chunks containing a cell whose straight line to its nearest
+    /// target passes by a changed cell are regenerated as well, since the line of sight of that cell may have changed
+    /// even if its cost did not. Returns the regenerated chunks. See [`FlowField::build`]
+    pub fn update(
+        &mut self,
+        changed: impl IntoIterator<Item = Cell>,
+        mut cost: impl FnMut(Cell, Cell) -> Option<f32>,
+    ) -> HashSet<Cell> {
+        let changed: Vec<Cell> = changed.into_iter().collect();
+        let recomputed = self.integration.update(changed.iter().copied(), &mut cost);
+
+        let topology = self.integration.topology;
+        let mut chunks = HashSet::new();
+        for cell in changed.iter().chain(recomputed.iter()) {
+            chunks.insert(self.chunk_of(*cell));
+            for neighbor in topology.neighbors(*cell) {
+                chunks.insert(self.chunk_of(neighbor));
+            }
+        }
+
+        // Steps along a line can depend on the cells around them, such as diagonal steps that may not cut corners
+        let near_changed: HashSet<Cell> = changed
+            .iter()
+            .flat_map(|cell| topology.neighbors(*cell).chain([*cell]))
+            .collect();
+        if let Some((min, max)) = bounds(near_changed.iter().copied()) {
+            let mut line_chunks = vec![];
+            for (chunk, reach) in self.chunk_reach.iter() {
+                if chunks.contains(chunk) {
+                    continue;
+                }
+                // No line from this chunk is longer than its reach, give or take rounding
+                let chunk_min = Cell::new(chunk.x * self.chunk_size, chunk.y * self.chunk_size);
+                let chunk_max = chunk_min + Cell::new(self.chunk_size - 1, self.chunk_size - 1);
+                let gap = (min.x - chunk_max.x)
+                    .max(chunk_min.x - max.x)
+                    .max(min.y - chunk_max.y)
+                    .max(chunk_min.y - max.y);
+                if gap > 0 && gap as u32 > reach + 1 {
+                    continue;
+                }
+                let crosses_change = self.chunk_cells(*chunk).any(|cell| {
+                    let Some(target) = self.nearest_target(cell) else {
+                        return false;
+                    };
+                    // Lines stay within the corners of the rectangle around their ends, give or take rounding
+                    let (line_min, line_max) = bounds([cell, target]).unwrap();
+                    if line_max.x + 1 < min.x
+                        || line_max.y + 1 < min.y
+                        || line_min.x - 1 > max.x
+                        || line_min.y - 1 > max.y
+                    {
+                        return false;
+                    }
+                    line(topology, cell, target)
+                        .iter()
+                        .any(|cell| near_changed.contains(cell))
+                });
+                if crosses_change {
+                    line_chunks.push(*chunk);
+                }
+            }
+            chunks.extend(line_chunks);
+        }
+
+        for chunk in chunks.iter() {
+            let cells: Vec<Cell> = self.chunk_cells(*chunk).collect();
+            for cell in cells {
+                self.regenerate_cell(cell, &mut cost);
+            }
+        }
+        chunks
+    }
+
+    /// Samples the flow at a world position on a square map, returning a normalized world space direction. Returns
+    /// zero if the position is outside the field or has no flow
+    #[cfg(feature = "square")]
+    pub fn sample_square(
+        &self,
+        layout: &crate::layout::square::SquareLayout,
+        pos: glam::Vec2,
+    ) -> glam::Vec2 {
+        let cell = layout.world_pos_to_cell(pos);
+        let Some(flow) = self.get(cell) else {
+            return glam::Vec2::ZERO;
+        };
+        let toward = match flow.line_of_sight {
+            Some(target) if target != cell => layout.cell_to_world_pos(target) - pos,
+            _ => layout.cell_to_world_pos(cell + flow.direction) - layout.cell_to_world_pos(cell),
+        };
+        toward.normalize_or_zero()
+    }
+
+    /// Samples the flow at a world position on a hexagonal map, returning a normalized world space direction. Returns
+    /// zero if the position is outside the field or has no flow
+    #[cfg(feature = "hex")]
+    pub fn sample_hex(&self, layout: &hexx::HexLayout, pos: hexx::Vec2) -> hexx::Vec2 {
+        let cell = Cell::from(layout.world_pos_to_hex(pos));
+        let Some(flow) = self.get(cell) else {
+            return hexx::Vec2::ZERO;
+        };
+        let toward = match flow.line_of_sight {
+            Some(target) if target != cell => layout.hex_to_world_pos(target.into()) - pos,
+            _ => {
+                layout.hex_to_world_pos((cell + flow.direction).into())
+                    - layout.hex_to_world_pos(cell.into())
+            }
+        };
+        toward.normalize_or_zero()
+    }
+
+    /// Recomputes the flow out of a single cell from the integration field
+    fn regenerate_cell(&mut self, cell: Cell, cost: &mut impl FnMut(Cell, Cell) -> Option<f32>) {
+        let topology = self.integration.topology;
+        let mut flow = Flow::default();
+
+        if let Some(current) = self.integration.get(cell).filter(|c| c.is_finite()) {
+            let mut best = current;
+            for neighbor in topology.neighbors(cell) {
+                let Some(value) = self.integration.get(neighbor) else {
+                    continue;
+                };
+                if value < best && cost(cell, neighbor).is_some() {
+                    best = value;
+                    flow.direction = neighbor - cell;
+                }
+            }
+
+            flow.line_of_sight = self
+                .nearest_target(cell)
+                .filter(|target| steps_clear(line(topology, cell, *target), cost));
+        }
+
+        self.flow.set(cell, flow);
+    }
+
+    /// The cells of the field inside the given chunk
+    fn chunk_cells(&self, chunk: Cell) -> impl Iterator<Item = Cell> + '_ {
+        let min = Cell::new(chunk.x * self.chunk_size, chunk.y * self.chunk_size);
+        (min.y..min.y + self.chunk_size)
+            .flat_map(move |y| (min.x..min.x + self.chunk_size).map(move |x| Cell::new(x, y)))
+            .filter(|cell| self.flow.contains(*cell))
+    }
+
+    /// Sorts the targets into chunks and records how far each chunk of the field is from its nearest targets
+    fn index_targets(&mut self) {
+        self.target_chunks.clear();
+        for (index, target) in self.targets.iter().enumerate() {
+            self.target_chunks
+                .entry(self.chunk_of(*target))
+                .or_default()
+                .push(index);
+        }
+
+        self.chunk_reach.clear();
+        let topology = self.integration.topology;
+        for (cell, _) in self.flow.iter() {
+            let Some(target) = self.nearest_target(cell) else {
+                continue;
+            };
+            let reach = self.chunk_reach.entry(self.chunk_of(cell)).or_default();
+            *reach = (*reach).max(topology.distance(cell, target));
+        }
+    }
+
+    /// The target the fewest steps away from the given cell, ignoring obstacles.
+    ///
+    /// Searches the chunks around the cell in growing rings, stopping once no target in the next ring could be
+    /// nearer. Every topology is at least as far as the largest of the `x` and `y` offsets, so a target `ring` chunks
+    /// out is more than `(ring - 1) * chunk_size` steps away
+    fn nearest_target(&self, cell: Cell) -> Option<Cell> {
+        let topology = self.integration.topology;
+        let origin = self.chunk_of(cell);
+        let furthest = self
+            .target_chunks
+            .keys()
+            .map(|chunk| chunk.x.abs_diff(origin.x).max(chunk.y.abs_diff(origin.y)))
+            .max()?;
+
+        // Ties go to the earliest target so the choice does not depend on the chunk size
+        let mut best: Option<(u32, usize)> = None;
+        for ring in 0..=furthest as i32 {
+            if best.is_some_and(|(distance, _)| {
+                ring > 0 && distance as i64 <= (ring as i64 - 1) * self.chunk_size as i64
+            }) {
+                break;
+            }
+            for dy in -ring..=ring {
+                let step = if dy.abs() == ring { 1 } else { 2 * ring.max(1) };
+                for dx in (-ring..=ring).step_by(step as usize) {
+                    let Some(indices) = self.target_chunks.get(&(origin + Cell::new(dx, dy)))
+                    else {
+                        continue;
+                    };
+                    for index in indices {
+                        let candidate = (topology.distance(cell, self.targets[*index]), *index);
+                        if best.is_none_or(|best| candidate < best) {
+                            best = Some(candidate);
+                        }
+                    }
+                }
+            }
+        }
+        best.map(|(_, index)| self.targets[index])
+    }
+}
+
+/// The smallest and largest `x` and `y` of the given cells. Returns None if there are no cells
+fn bounds(cells: impl IntoIterator<Item = Cell>) -> Option<(Cell, Cell)> {
+    let mut cells = cells.into_iter();
+    let first = cells.next()?;
+    Some(cells.fold((first, first), |(min, max), cell| {
+        (
+            Cell::new(min.x.min(cell.x), min.y.min(cell.y)),
+            Cell::new(max.x.max(cell.x), max.y.max(cell.y)),
+        )
+    }))
+}
+
+/// The cells along the straight line between two cells, both ends included
+fn line(topology: Topology, from: Cell, to: Cell) -> Vec<Cell> {
+    match topology {
+        #[cfg(feature = "hex")]
+        Topology::Hex => hexx::Hex::from(from)
+            .line_to(to.into())
+            .map(Cell::from)
+            .collect(),
+        #[cfg(feature = "square")]
+        Topology::Square(_) => from.square_walk_to(to).collect(),
+    }
+}
+
+/// Whether every step along the line is possible
+fn steps_clear(line: Vec<Cell>, cost: &mut impl FnMut(Cell, Cell) -> Option<f32>) -> bool {
+    let mut line = line.into_iter();
+    let Some(mut previous) = line.next() else {
+        return true;
+    };
+    line.all(|cell| {
+        let clear = cost(previous, cell).is_some();
+        previous = cell;
+        clear
+    })
+}
+
+#[cfg(all(test, feature = "square"))]
+mod tests {
+    use glam::Vec2;
+
+    use crate::cell::{square::SquareConnectivity, topology::Topology, Cell};
+    use crate::layout::square::SquareLayout;
+    use crate::storage::{grid::Grid, GridStorage};
+
+    use super::{Flow, FlowField};
+
+    const EIGHT: Topology = Topology::Square(SquareConnectivity::Eight);
+
+    fn cost(walls: &[Cell]) -> impl Fn(Cell, Cell) -> Option<f32> + '_ {
+        move |from, to| {
+            if walls.contains(&from) || walls.contains(&to) {
+                return None;
+            }
+            // Diagonal steps may not cut the corners of walls
+            let corner_a = Cell::new(to.x, from.y);
+            let corner_b = Cell::new(from.x, to.y);
+            if walls.contains(&corner_a) || walls.contains(&corner_b) {
+                return None;
+            }
+            Some(from.octile_distance(to, std::f32::consts::SQRT_2))
+        }
+    }
+
+    fn new_field(rows: usize, cols: usize) -> FlowField<Grid<f32>, Grid<Flow>> {
+        FlowField::new(Grid::init(rows, cols, 0.0), Grid::new(rows, cols), EIGHT)
+    }
+
+    #[test]
+    fn test_directions_reach_target() {
+        let walls: Vec<Cell> = (1..8).map(|y| Cell::new(4, y)).collect();
+        let mut field = new_field(8, 8);
+        field.build([Cell::new(7, 7)], cost(&walls));
+
+        for (start, _) in GridStorage::iter(&field.flow) {
+            if walls.contains(&start) {
+                continue;
+            }
+            let mut cell = start;
+            for _ in 0..64 {
+                let flow = field.get(cell).unwrap();
+                if flow.direction == Cell::ZERO {
+                    break;
+                }
+                cell = cell + flow.direction;
+                assert!(!walls.contains(&cell));
+            }
+            assert_eq!(cell, Cell::new(7, 7), "{}", start);
+        }
+    }
+
+    #[test]
+    fn test_line_of_sight() {
+        let walls = [Cell::new(3, 3), Cell::new(3, 4), Cell::new(3, 5)];
+        let mut field = new_field(8, 8);
+        field.build([Cell::new(6, 4)], cost(&walls));
+
+        assert_eq!(
+            field.get(Cell::new(5, 0)).unwrap().line_of_sight,
+            Some(Cell::new(6, 4))
+        );
+        // The wall hides the target from directly behind it
+        assert_eq!(field.get(Cell::new(0, 4)).unwrap().line_of_sight, None);
+        assert_eq!(field.get(Cell::new(3, 4)).unwrap(), &Flow::default());
+    }
+
+    #[test]
+    fn test_sample() {
+        let layout = SquareLayout::default();
+        let mut field = new_field(5, 5);
+        field.build([Cell::new(4, 2)], cost(&[]));
+
+        let direction = field.sample_square(&layout, Vec2::new(0.0, 0.0));
+        assert!((direction - Vec2::new(4.0, 2.0).normalize()).length() < 0.001);
+        assert_eq!(
+            field.sample_square(&layout, Vec2::new(-3.0, 0.0)),
+            Vec2::ZERO
+        );
+    }
+
+    #[test]
+    fn test_chunked_update() {
+        let mut walls = vec![];
+        let mut field = new_field(12, 12).with_chunk_size(4);
+        field.build([Cell::new(0, 0)], cost(&walls));
+
+        walls.extend((0..6).map(|x| Cell::new(x, 8)));
+        let chunks = field.update(walls.clone(), cost(&walls));
+        assert!(chunks.contains(&Cell::new(0, 2)));
+
+        let mut expected = new_field(12, 12);
+        expected.build([Cell::new(0, 0)], cost(&walls));
+        assert_eq!(field.integration.costs, expected.integration.costs);
+        assert_eq!(field.flow, expected.flow);
+    }
+
+    #[test]
+    fn test_update_removed_wall() {
+        let mut walls = vec![Cell::new(10, 5)];
+        let mut field = new_field(32, 32).with_chunk_size(4);
+        field.build([Cell::new(0, 0)], cost(&walls));
+        assert_eq!(field.get(Cell::new(13, 6)).unwrap().line_of_sight, None);
+
+        // Removing the wall changes no costs, but clears the line of sight of the cells behind it
+        walls.clear();
+        let chunks = field.update([Cell::new(10, 5)], cost(&walls));
+        assert!(chunks.contains(&field.chunk_of(Cell::new(13, 6))));
+        assert!(chunks.len() < 64);
+
+        let mut expected = new_field(32, 32);
+        expected.build([Cell::new(0, 0)], cost(&walls));
+        assert_eq!(field.integration.costs, expected.integration.costs);
+        assert_eq!(field.flow, expected.flow);
+    }
+
+    #[test]
+    fn test_target_region() {
+        let targets: Vec<Cell> = (20..24)
+            .flat_map(|y| (2..30).map(move |x| Cell::new(x, y)))
+            .collect();
+        let mut field = new_field(32, 32).with_chunk_size(4);
+        field.build(targets.iter().copied(), cost(&[]));
+
+        for (cell, flow) in GridStorage::iter(&field.flow) {
+            let nearest = targets
+                .iter()
+                .min_by_key(|target| EIGHT.distance(cell, **target))
+                .copied();
+            assert_eq!(flow.line_of_sight, nearest, "{}", cell);
+        }
+    }
+
+    #[cfg(feature = "hex")]
+    #[test]
+    fn test_hex_field() {
+        use hexx::{HexLayout, HexOrientation};
+
+        use crate::storage::hex::HexRectangleStorage;
+
+        let orientation = HexOrientation::Flat;
+        let mut field = FlowField::new(
+            HexRectangleStorage::new_uniform(6, 6, 0.0, orientation),
+            HexRectangleStorage::new(6, 6, orientation),
+            Topology::Hex,
+        );
+        field.build([Cell::new(2, 1)], |_, _| Some(1.0));
+
+        let flow = field.get(Cell::new(0, 1)).unwrap();
+        assert_eq!(flow.direction, Cell::new(1, 0));
+        assert_eq!(flow.line_of_sight, Some(Cell::new(2, 1)));
+
+        let layout = HexLayout {
+            orientation,
+            ..Default::default()
+        };
+        let pos = layout.hex_to_world_pos(hexx::Hex::new(0, 1));
+        let target = layout.hex_to_world_pos(hexx::Hex::new(2, 1));
+        let direction = field.sample_hex(&layout, pos);
+        assert!((direction - (target - pos).normalize()).length() < 0.001);
+    }
+}
@@ -0,0 +1,41 @@
+//! Pathfinding over [`Cell`]s for both hexagonal and square maps.
+//!
+//! The shape of the map is described with a [`Topology`](crate::cell::topology::Topology) and the map data is read
+//! through closures or any [`GridStorage`](crate::storage::GridStorage).
+
+use std::cmp::Ordering;
+
+use crate::cell::Cell;
+
+pub mod astar;
+pub mod dijkstra;
+pub mod flow_field;
+pub mod movement;
+
+/// An entry in a min-heap of cells ordered by ascending score
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct ScoredCell {
+    pub score: f32,
+    pub cell: Cell,
+}
+
+impl PartialEq for ScoredCell {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for ScoredCell {}
+
+impl PartialOrd for ScoredCell {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ScoredCell {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Reversed so that `BinaryHeap` pops the lowest score first
+        other.score.total_cmp(&self.score)
+    }
+}
@@ -0,0 +1,216 @@
+use std::collections::{BinaryHeap, HashMap};
+
+use crate::{cell::topology::Topology, cell::Cell, storage::GridStorage};
+
+use super::ScoredCell;
+
+/// A cell reachable within a [`MovementRange`]
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Reachable {
+    /// The movement points left after reaching the cell
+    pub remaining: f32,
+    /// The cell moved from to reach this cell. None for the starting cell
+    pub predecessor: Option<Cell>,
+}
+
+/// Every cell a unit can reach with its movement points. See [`movement_range`]
+#[derive(Clone, Debug)]
+pub struct MovementRange {
+    pub start: Cell,
+    pub cells: HashMap<Cell, Reachable>,
+}
+
+impl MovementRange {
+    /// Returns the given cell if it is reachable
+    pub fn get(&self, cell: Cell) -> Option<&Reachable> {
+        self.cells.get(&cell)
+    }
+
+    /// Returns true if the given cell is reachable
+    pub fn contains(&self, cell: Cell) -> bool {
+        self.cells.contains_key(&cell)
+    }
+
+    /// Rebuilds the cheapest path from the start to the given cell, including both ends. Returns None if the cell is
+    /// not reachable
+    pub fn path_to(&self, cell: Cell) -> Option<Vec<Cell>> {
+        let mut path = vec![cell];
+        let mut current = self.cells.get(&cell)?;
+        while let Some(predecessor) = current.predecessor {
+            path.push(predecessor);
+            current = &self.cells[&predecessor];
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+/// Finds every cell reachable from `start` without spending more than `budget` movement points.
+///
+/// - `cost` returns the movement points needed to enter a cell with the given data, or None if it is impassable.
+///   Cells outside the storage are impassable
+/// - `zone_of_control` returns whether a cell is controlled by an enemy. Entering a controlled cell ends movement,
+///   so no cell is reached through it. A unit starting in a controlled cell can still move out of it. Use `|_| false`
+///   to ignore zones of control, or [`adjacent_to`] to control every cell next to an enemy
+pub fn movement_range<T>(
+    storage: &impl GridStorage<T>,
+    start: Cell,
+    budget: f32,
+    topology: Topology,
+    cost: impl Fn(&T) -> Option<f32>,
+    zone_of_control: impl Fn(Cell) -> bool,
+) -> MovementRange {
+    let mut cells = HashMap::new();
+    let mut open = BinaryHeap::new();
+    cells.insert(
+        start,
+        Reachable {
+            remaining: budget,
+            predecessor: None,
+        },
+    );
+    open.push(ScoredCell {
+        score: 0.0,
+        cell: start,
+    });
+
+    while let Some(ScoredCell { score: spent, cell }) = open.pop() {
+        if budget - spent < cells[&cell].remaining {
+            continue;
+        }
+        if cell != start && zone_of_control(cell) {
+            continue;
+        }
+        for neighbor in topology.neighbors(cell) {
+            let Some(step) = storage.get(neighbor).and_then(&cost) else {
+                continue;
+            };
+            let neighbor_spent = spent + step;
+            if neighbor_spent > budget
+                || cells
+                    .get(&neighbor)
+                    .is_some_and(|reached: &Reachable| reached.remaining >= budget - neighbor_spent)
+            {
+                continue;
+            }
+            cells.insert(
+                neighbor,
+                Reachable {
+                    remaining: budget - neighbor_spent,
+                    predecessor: Some(cell),
+                },
+            );
+            open.push(ScoredCell {
+                score: neighbor_spent,
+                cell: neighbor,
+            });
+        }
+    }
+
+    MovementRange { start, cells }
+}
+
+/// A zone of control rule controlling every cell next to one of the given enemies. See [`movement_range`]
+pub fn adjacent_to(enemies: &[Cell], topology: Topology) -> impl Fn(Cell) -> bool + '_ {
+    move |cell| {
+        topology
+            .neighbors(cell)
+            .any(|neighbor| enemies.contains(&neighbor))
+    }
+}
+
+#[cfg(all(test, feature = "square"))]
+mod tests {
+    use crate::cell::{square::SquareConnectivity, topology::Topology, Cell};
+    use crate::storage::square::SquareStorage;
+
+    use super::{adjacent_to, movement_range};
+
+    const FOUR: Topology = Topology::Square(SquareConnectivity::Four);
+
+    #[test]
+    fn test_budget() {
+        let map = SquareStorage::new_uniform(9, 9, 1.0);
+        let range = movement_range(&map, Cell::new(4, 4), 2.0, FOUR, |c| Some(*c), |_| false);
+
+        assert_eq!(range.cells.len(), 13);
+        assert_eq!(range.get(Cell::new(4, 4)).unwrap().remaining, 2.0);
+        assert_eq!(range.get(Cell::new(5, 5)).unwrap().remaining, 0.0);
+        assert!(!range.contains(Cell::new(7, 4)));
+    }
+
+    #[test]
+    fn test_costs_and_path() {
+        // Forest costs 3, the wall is impassable
+        let map = SquareStorage::new_from_vec(vec![
+            vec![Some(1.0), Some(3.0), Some(1.0), Some(1.0)],
+            vec![Some(1.0), None, Some(1.0), Some(1.0)],
+            vec![Some(1.0), Some(1.0), Some(1.0), Some(1.0)],
+        ]);
+        let range = movement_range(&map, Cell::new(0, 0), 4.0, FOUR, |c| *c, |_| false);
+
+        assert!(!range.contains(Cell::new(1, 1)));
+        assert_eq!(range.get(Cell::new(2, 0)).unwrap().remaining, 0.0);
+        assert_eq!(range.get(Cell::new(2, 2)).unwrap().remaining, 0.0);
+        assert_eq!(
+            range.path_to(Cell::new(2, 2)),
+            Some(vec![
+                Cell::new(0, 0),
+                Cell::new(0, 1),
+                Cell::new(0, 2),
+                Cell::new(1, 2),
+                Cell::new(2, 2)
+            ])
+        );
+        assert_eq!(range.path_to(Cell::new(3, 2)), None);
+    }
+
+    #[test]
+    fn test_zone_of_control() {
+        let map = SquareStorage::new_uniform(7, 1, 1.0);
+        let enemies = [Cell::new(4, 0)];
+        let range = movement_range(
+            &map,
+            Cell::new(0, 0),
+            6.0,
+            FOUR,
+            |c| Some(*c),
+            adjacent_to(&enemies, FOUR),
+        );
+
+        // Movement stops next to the enemy, so nothing past it is reachable
+        assert!(range.contains(Cell::new(3, 0)));
+        assert!(!range.contains(Cell::new(4, 0)));
+        assert!(!range.contains(Cell::new(5, 0)));
+
+        // Starting inside the zone does not stop movement
+        let range = movement_range(
+            &map,
+            Cell::new(5, 0),
+            1.0,
+            FOUR,
+            |c| Some(*c),
+            adjacent_to(&enemies, FOUR),
+        );
+        assert!(range.contains(Cell::new(6, 0)));
+    }
+
+    #[cfg(feature = "hex")]
+    #[test]
+    fn test_hex_range() {
+        use hexx::HexOrientation;
+
+        use crate::storage::hex::HexRectangleStorage;
+
+        let map = HexRectangleStorage::new_uniform(9, 9, 1.0, HexOrientation::Pointy);
+        let range = movement_range(
+            &map,
+            Cell::new(2, 4),
+            2.0,
+            Topology::Hex,
+            |c| Some(*c),
+            |_| false,
+        );
+        assert_eq!(range.cells.len(), 19);
+    }
+}
@@ -0,0 +1,237 @@
+//! Flood fill and connected-component labeling over any [`GridStorage`].
+//!
+//! Cells are connected through the neighbors of a [`Topology`], so the same functions work for 4 and 8 connected
+//! square maps and for hexagonal maps.
+
+use std::collections::{HashSet, VecDeque};
+
+use crate::{cell::topology::Topology, cell::Cell, storage::GridStorage};
+
+/// The label given to cells that are not part of any region by [`label_components`]
+pub const NO_REGION: u32 = 0;
+
+/// A group of connected cells found by [`label_components`]
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Region {
+    /// The label written for every cell of the region. Labels start at 1
+    pub id: u32,
+    /// Every cell in the region in the order they were reached
+    pub cells: Vec<Cell>,
+    /// The smallest x and y of any cell in the region
+    pub min: Cell,
+    /// The largest x and y of any cell in the region
+    pub max: Cell,
+}
+
+impl Region {
+    /// The number of cells in the region
+    pub fn size(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Returns true if the given cell is inside the bounding box of the region
+    pub fn bounds_contain(&self, cell: Cell) -> bool {
+        (self.min.x..=self.max.x).contains(&cell.x) && (self.min.y..=self.max.y).contains(&cell.y)
+    }
+}
+
+/// Finds every cell connected to `seed` through cells where `include` is true, in breadth first order.
+///
+/// Returns an empty vec if the seed is outside the storage or not included itself
+pub fn flood_fill<T>(
+    storage: &impl GridStorage<T>,
+    seed: Cell,
+    topology: Topology,
+    include: impl Fn(&T) -> bool,
+) -> Vec<Cell> {
+    let mut visited = HashSet::new();
+    fill(storage, seed, topology, &include, |cell| {
+        visited.insert(cell)
+    })
+}
+
+/// Labels every group of connected cells where `include` is true.
+///
+/// Each cell of the storage is written into `labels` with the id of its region, or [`NO_REGION`] if it is not
+/// included. Region ids are assigned in the iteration order of the storage starting from 1. `labels` must contain
+/// every cell of the storage, for example a `Grid<u32>` with the same dimensions as a `Grid<T>`
+pub fn label_components<T>(
+    storage: &impl GridStorage<T>,
+    topology: Topology,
+    include: impl Fn(&T) -> bool,
+    labels: &mut impl GridStorage<u32>,
+) -> Vec<Region> {
+    for (cell, _) in storage.iter() {
+        labels.set(cell, NO_REGION);
+    }
+
+    let mut regions = Vec::new();
+    for (seed, data) in storage.iter() {
+        if !include(data) || labels.get(seed).is_some_and(|label| *label != NO_REGION) {
+            continue;
+        }
+
+        let id = regions.len() as u32 + 1;
+        let cells = fill(storage, seed, topology, &include, |cell| {
+            labels.set(cell, id) == Some(NO_REGION)
+        });
+        let min = cells.iter().fold(seed, |min, cell| {
+            Cell::new(min.x.min(cell.x), min.y.min(cell.y))
+        });
+        let max = cells.iter().fold(seed, |max, cell| {
+            Cell::new(max.x.max(cell.x), max.y.max(cell.y))
+        });
+        regions.push(Region {
+            id,
+            cells,
+            min,
+            max,
+        });
+    }
+    regions
+}
+
+/// Returns true if every cell where `include` is true can reach every other. A storage with no included cells is
+/// connected
+pub fn is_connected<T>(
+    storage: &impl GridStorage<T>,
+    topology: Topology,
+    include: impl Fn(&T) -> bool,
+) -> bool {
+    let Some((seed, _)) = storage.iter().find(|(_, data)| include(data)) else {
+        return true;
+    };
+    let included = storage.iter().filter(|(_, data)| include(data)).count();
+    flood_fill(storage, seed, topology, include).len() == included
+}
+
+/// Breadth first fill from `seed`. `visit` marks a cell as reached and returns false if it was reached before
+fn fill<T>(
+    storage: &impl GridStorage<T>,
+    seed: Cell,
+    topology: Topology,
+    include: &impl Fn(&T) -> bool,
+    mut visit: impl FnMut(Cell) -> bool,
+) -> Vec<Cell> {
+    if !storage.get(seed).is_some_and(include) || !visit(seed) {
+        return Vec::new();
+    }
+
+    let mut cells = Vec::new();
+    let mut open = VecDeque::from([seed]);
+    while let Some(cell) = open.pop_front() {
+        cells.push(cell);
+        for neighbor in topology.neighbors(cell) {
+            if storage.get(neighbor).is_some_and(include) && visit(neighbor) {
+                open.push_back(neighbor);
+            }
+        }
+    }
+    cells
+}
+
+#[cfg(all(test, feature = "square"))]
+mod tests {
+    use crate::cell::{square::SquareConnectivity, topology::Topology, Cell};
+    use crate::storage::{grid::Grid, square::SquareStorage, GridStorage};
+
+    use super::{flood_fill, is_connected, label_components, NO_REGION};
+
+    const FOUR: Topology = Topology::Square(SquareConnectivity::Four);
+    const EIGHT: Topology = Topology::Square(SquareConnectivity::Eight);
+
+    fn islands() -> SquareStorage<bool> {
+        SquareStorage::new_from_vec(vec![
+            vec![true, true, false, false, false],
+            vec![true, false, false, true, false],
+            vec![false, true, false, true, true],
+            vec![false, false, false, false, false],
+        ])
+    }
+
+    #[test]
+    fn test_flood_fill() {
+        let map = islands();
+
+        let filled = flood_fill(&map, Cell::new(0, 0), FOUR, |land| *land);
+        assert_eq!(
+            filled,
+            vec![Cell::new(0, 0), Cell::new(1, 0), Cell::new(0, 1)]
+        );
+        assert_eq!(
+            flood_fill(&map, Cell::new(0, 0), EIGHT, |land| *land).len(),
+            4
+        );
+
+        let lake = flood_fill(&map, Cell::new(2, 0), FOUR, |land| !*land);
+        assert_eq!(lake.len(), 13);
+        assert!(flood_fill(&map, Cell::new(1, 1), FOUR, |land| *land).is_empty());
+        assert!(flood_fill(&map, Cell::new(-1, 0), FOUR, |_| true).is_empty());
+    }
+
+    #[test]
+    fn test_labeling() {
+        let map = islands();
+        let mut labels = SquareStorage::new(5, 4);
+
+        let regions = label_components(&map, FOUR, |land| *land, &mut labels);
+        assert_eq!(regions.len(), 3);
+        assert_eq!(
+            regions.iter().map(|r| r.size()).collect::<Vec<_>>(),
+            vec![3, 3, 1]
+        );
+        assert_eq!(regions[1].min, Cell::new(3, 1));
+        assert_eq!(regions[1].max, Cell::new(4, 2));
+        assert!(regions[1].bounds_contain(Cell::new(4, 1)));
+        assert_eq!(labels.get(Cell::new(4, 2)), Some(&2));
+        assert_eq!(labels.get(Cell::new(1, 2)), Some(&3));
+        assert_eq!(labels.get(Cell::new(2, 2)), Some(&NO_REGION));
+
+        let regions = label_components(&map, EIGHT, |land| *land, &mut labels);
+        assert_eq!(regions.len(), 2);
+        assert_eq!(labels.get(Cell::new(1, 2)), Some(&1));
+    }
+
+    #[test]
+    fn test_grid_labeling() {
+        let map = Grid::from_vec(vec![1, 1, 2, 2, 2, 2, 1, 1, 1], 3);
+        let mut labels = Grid::new(3, 3);
+
+        let regions = label_components(&map, FOUR, |t| *t == 1, &mut labels);
+        assert_eq!(regions.len(), 2);
+        assert_eq!(GridStorage::get(&labels, Cell::new(2, 2)), Some(&2));
+        assert_eq!(GridStorage::get(&labels, Cell::new(1, 0)), Some(&1));
+        assert_eq!(GridStorage::get(&labels, Cell::new(0, 1)), Some(&NO_REGION));
+    }
+
+    #[test]
+    fn test_connected() {
+        let map = islands();
+        assert!(!is_connected(&map, EIGHT, |land| *land));
+        assert!(is_connected(&map, FOUR, |land| !*land));
+        assert!(is_connected(&map, FOUR, |_| false));
+    }
+
+    #[cfg(feature = "hex")]
+    #[test]
+    fn test_hex_regions() {
+        use crate::storage::hex::HexRectangleStorage;
+        use crate::HexOrientation;
+
+        let mut map = HexRectangleStorage::new_uniform(5, 5, true, HexOrientation::Pointy);
+        for (cell, _) in GridStorage::iter(&map.clone()) {
+            if cell.x + cell.y == 2 || cell.x + cell.y == 3 {
+                map.set(cell, false);
+            }
+        }
+        let mut labels = HexRectangleStorage::new_uniform(5, 5, 0, HexOrientation::Pointy);
+
+        let regions = label_components(&map, Topology::Hex, |t| *t, &mut labels);
+        assert_eq!(regions.len(), 2);
+        assert!(!is_connected(&map, Topology::Hex, |t| *t));
+        assert_eq!(
+            regions.iter().map(|r| r.size()).sum::<usize>(),
+            25 - map.grid.iter().filter(|t| !**t).count()
+        );
+    }
+}
@@ -0,0 +1,467 @@
+use std::collections::HashMap;
+use std::fmt::Debug;
+
+use glam::UVec2;
+
+#[cfg(feature = "hex")]
+use hexx::{Hex, OffsetHexMode};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "bevy_reflect")]
+use bevy::prelude::Reflect;
+
+use crate::{cell::Cell, error::LettucesError};
+
+use super::{grid::Grid, GridStorage};
+
+/// How cells are laid out inside the chunks of a [`ChunkedStorage`]
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+// Reflected as an opaque value since hexx does not reflect its offset modes for the version of bevy used here
+#[cfg_attr(feature = "bevy_reflect", derive(Reflect), reflect_value(PartialEq, Debug))]
+pub enum ChunkLayout {
+    /// Chunks are squares of cells
+    #[default]
+    Square,
+    /// Chunks are rectangles of hexagons in the offset coordinates of the given mode, so that they line up on screen
+    #[cfg(feature = "hex")]
+    Hex(OffsetHexMode),
+}
+
+impl ChunkLayout {
+    /// The position of the cell on the rectangular plane that is split into chunks
+    fn plane_position(self, cell: Cell) -> [i32; 2] {
+        match self {
+            ChunkLayout::Square => [cell.x, cell.y],
+            #[cfg(feature = "hex")]
+            ChunkLayout::Hex(mode) => Hex::from(cell).to_offset_coordinates(mode),
+        }
+    }
+
+    /// The inverse of [`ChunkLayout::plane_position`]
+    fn cell_at(self, position: [i32; 2]) -> Cell {
+        match self {
+            ChunkLayout::Square => Cell::new(position[0], position[1]),
+            #[cfg(feature = "hex")]
+            ChunkLayout::Hex(mode) => Cell::from_offset_coordinates(position, mode),
+        }
+    }
+}
+
+/// A single chunk of a [`ChunkedStorage`]
+#[derive(Hash, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bevy_reflect", derive(Reflect))]
+pub struct Chunk<T> {
+    pub grid: Grid<T>,
+    /// Set whenever data in the chunk is accessed mutably through the storage. Cleared by
+    /// [`ChunkedStorage::take_dirty`]
+    pub dirty: bool,
+}
+
+type LoadHook<T> = Box<dyn FnMut(Cell, &mut Chunk<T>) + Send + Sync>;
+type UnloadHook<T> = Box<dyn FnMut(Cell, Chunk<T>) + Send + Sync>;
+
+/// Storage for unbounded maps that splits the plane into fixed size chunks.
+///
+/// Chunks are keyed by their chunk coordinates, where chunk `(0, 0)` holds the cells from `(0, 0)` up to the chunk
+/// size and chunk `(-1, 0)` holds the cells directly to its left. Chunks are only allocated when they are loaded,
+/// either explicitly through [`ChunkedStorage::load_chunk`] or by setting a cell inside them. Cells in chunks that
+/// are not loaded are outside the storage.
+pub struct ChunkedStorage<T> {
+    chunks: HashMap<Cell, Chunk<T>>,
+    chunk_size: UVec2,
+    layout: ChunkLayout,
+    on_load: Option<LoadHook<T>>,
+    on_unload: Option<UnloadHook<T>>,
+}
+
+impl<T> ChunkedStorage<T> {
+    /// Construct a new empty [`ChunkedStorage`] with chunks of `chunk_size` cells.
+    ///
+    /// # Panics
+    /// If either side of the chunk size is 0
+    pub fn new(chunk_size: UVec2, layout: ChunkLayout) -> ChunkedStorage<T> {
+        assert!(chunk_size.x > 0 && chunk_size.y > 0);
+        ChunkedStorage {
+            chunks: HashMap::new(),
+            chunk_size,
+            layout,
+            on_load: None,
+            on_unload: None,
+        }
+    }
+
+    /// Sets a hook that is called with every newly loaded chunk after it is filled with default data, for example to
+    /// generate terrain or read the chunk from disk
+    pub fn with_load_hook(
+        mut self,
+        hook: impl FnMut(Cell, &mut Chunk<T>) + Send + Sync + 'static,
+    ) -> ChunkedStorage<T> {
+        self.on_load = Some(Box::new(hook));
+        self
+    }
+
+    /// Sets a hook that is given every chunk as it is unloaded, for example to save dirty chunks to disk
+    pub fn with_unload_hook(
+        mut self,
+        hook: impl FnMut(Cell, Chunk<T>) + Send + Sync + 'static,
+    ) -> ChunkedStorage<T> {
+        self.on_unload = Some(Box::new(hook));
+        self
+    }
+
+    pub fn chunk_size(&self) -> UVec2 {
+        self.chunk_size
+    }
+
+    pub fn layout(&self) -> ChunkLayout {
+        self.layout
+    }
+
+    /// Returns the coordinates of the chunk containing the given cell
+    pub fn chunk_of(&self, cell: Cell) -> Cell {
+        self.chunk_and_index(cell).0
+    }
+
+    /// Iterates over every cell inside the given chunk, whether it is loaded or not
+    pub fn chunk_cells(&self, chunk: Cell) -> impl Iterator<Item = Cell> {
+        let (size, layout) = (self.chunk_size, self.layout);
+        (0..size.x as usize * size.y as usize).map(move |i| chunk_cell(layout, size, chunk, i))
+    }
+
+    /// Returns true if the given chunk is loaded
+    pub fn is_loaded(&self, chunk: Cell) -> bool {
+        self.chunks.contains_key(&chunk)
+    }
+
+    /// Access a loaded chunk
+    pub fn chunk(&self, chunk: Cell) -> Option<&Chunk<T>> {
+        self.chunks.get(&chunk)
+    }
+
+    /// Access a loaded chunk mutably. Does not mark the chunk as dirty
+    pub fn chunk_mut(&mut self, chunk: Cell) -> Option<&mut Chunk<T>> {
+        self.chunks.get_mut(&chunk)
+    }
+
+    /// Loads the given chunk if it is not loaded yet, filling it with default data and calling the load hook, then
+    /// returns it
+    pub fn load_chunk(&mut self, chunk: Cell) -> &mut Chunk<T>
+    where
+        T: Default,
+    {
+        let size = self.chunk_size;
+        let on_load = &mut self.on_load;
+        self.chunks.entry(chunk).or_insert_with(|| {
+            let mut new = Chunk {
+                grid: Grid::new(size.y as usize, size.x as usize),
+                dirty: false,
+            };
+            if let Some(hook) = on_load {
+                hook(chunk, &mut new);
+            }
+            new
+        })
+    }
+
+    /// Unloads the given chunk, passing it to the unload hook. Returns false if the chunk was not loaded
+    pub fn unload_chunk(&mut self, chunk: Cell) -> bool {
+        let Some(removed) = self.chunks.remove(&chunk) else {
+            return false;
+        };
+        if let Some(hook) = &mut self.on_unload {
+            hook(chunk, removed);
+        }
+        true
+    }
+
+    /// Unloads every chunk for which `keep` returns false, passing each to the unload hook
+    pub fn unload_where(&mut self, mut keep: impl FnMut(Cell, &Chunk<T>) -> bool) {
+        let unloaded: Vec<Cell> = self
+            .chunks
+            .iter()
+            .filter(|(chunk, data)| !keep(**chunk, data))
+            .map(|(chunk, _)| *chunk)
+            .collect();
+        for chunk in unloaded {
+            self.unload_chunk(chunk);
+        }
+    }
+
+    /// Iterates over every loaded chunk in no particular order
+    pub fn chunks(&self) -> impl Iterator<Item = (Cell, &Chunk<T>)> {
+        self.chunks.iter().map(|(chunk, data)| (*chunk, data))
+    }
+
+    /// Iterates mutably over every loaded chunk in no particular order. Does not mark the chunks as dirty
+    pub fn chunks_mut(&mut self) -> impl Iterator<Item = (Cell, &mut Chunk<T>)> {
+        self.chunks.iter_mut().map(|(chunk, data)| (*chunk, data))
+    }
+
+    /// Iterates over the coordinates of every dirty chunk
+    pub fn dirty_chunks(&self) -> impl Iterator<Item = Cell> + '_ {
+        self.chunks
+            .iter()
+            .filter(|(_, data)| data.dirty)
+            .map(|(chunk, _)| *chunk)
+    }
+
+    /// Returns the coordinates of every dirty chunk and clears their dirty flags
+    pub fn take_dirty(&mut self) -> Vec<Cell> {
+        self.chunks
+            .iter_mut()
+            .filter(|(_, data)| data.dirty)
+            .map(|(chunk, data)| {
+                data.dirty = false;
+                *chunk
+            })
+            .collect()
+    }
+
+    /// Access data inside the storage. Returns None if the chunk containing the cell is not loaded
+    pub fn get(&self, cell: Cell) -> Option<&T> {
+        let (chunk, [col, row]) = self.chunk_and_index(cell);
+        self.chunks.get(&chunk)?.grid.get(row, col)
+    }
+
+    /// Access data mutably inside the storage, marking its chunk as dirty. Returns None if the chunk containing the
+    /// cell is not loaded
+    pub fn get_mut(&mut self, cell: Cell) -> Option<&mut T> {
+        let (chunk, [col, row]) = self.chunk_and_index(cell);
+        let chunk = self.chunks.get_mut(&chunk)?;
+        chunk.dirty = true;
+        chunk.grid.get_mut(row, col)
+    }
+
+    /// Sets the data at the given Cell, returning the previous data. Loads the chunk containing the cell if it is not
+    /// loaded yet and marks it as dirty
+    pub fn set(&mut self, cell: Cell, data: T) -> Option<T>
+    where
+        T: Default,
+    {
+        let (chunk, [col, row]) = self.chunk_and_index(cell);
+        let chunk = self.load_chunk(chunk);
+        chunk.dirty = true;
+        let t = chunk.grid.get_mut(row, col)?;
+        Some(std::mem::replace(t, data))
+    }
+
+    /// Iterates over every cell in the loaded chunks alongside its data. Chunks are visited in no particular order
+    pub fn iter(&self) -> impl Iterator<Item = (Cell, &T)> {
+        let (size, layout) = (self.chunk_size, self.layout);
+        self.chunks.iter().flat_map(move |(chunk, data)| {
+            let chunk = *chunk;
+            data.grid
+                .iter()
+                .enumerate()
+                .map(move |(i, t)| (chunk_cell(layout, size, chunk, i), t))
+        })
+    }
+
+    /// Iterates mutably over every cell in the loaded chunks alongside its data, marking every chunk as dirty
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Cell, &mut T)> {
+        let (size, layout) = (self.chunk_size, self.layout);
+        self.chunks.iter_mut().flat_map(move |(chunk, data)| {
+            let chunk = *chunk;
+            data.dirty = true;
+            data.grid
+                .iter_mut()
+                .enumerate()
+                .map(move |(i, t)| (chunk_cell(layout, size, chunk, i), t))
+        })
+    }
+
+    /// The size in cells of the smallest rectangle of chunks containing every loaded chunk
+    pub fn dimensions(&self) -> UVec2 {
+        let mut chunks = self.chunks.keys();
+        let Some(first) = chunks.next() else {
+            return UVec2::ZERO;
+        };
+        let (min, max) = chunks.fold((*first, *first), |(min, max), chunk| {
+            (
+                Cell::new(min.x.min(chunk.x), min.y.min(chunk.y)),
+                Cell::new(max.x.max(chunk.x), max.y.max(chunk.y)),
+            )
+        });
+        // Chunks far apart can span more cells than fit in a u32, so the size saturates
+        let span = |min: i32, max: i32, size: u32| {
+            ((max as i64 - min as i64 + 1) as u64)
+                .saturating_mul(size as u64)
+                .min(u32::MAX as u64) as u32
+        };
+        UVec2::new(
+            span(min.x, max.x, self.chunk_size.x),
+            span(min.y, max.y, self.chunk_size.y),
+        )
+    }
+
+    /// Returns the chunk containing the cell and the `[col, row]` index of the cell inside the chunk
+    fn chunk_and_index(&self, cell: Cell) -> (Cell, [usize; 2]) {
+        let [x, y] = self.layout.plane_position(cell);
+        let (width, height) = (self.chunk_size.x as i32, self.chunk_size.y as i32);
+        (
+            Cell::new(x.div_euclid(width), y.div_euclid(height)),
+            [x.rem_euclid(width) as usize, y.rem_euclid(height) as usize],
+        )
+    }
+}
+
+/// Returns the cell at index `i` of the grid of the given chunk
+fn chunk_cell(layout: ChunkLayout, size: UVec2, chunk: Cell, i: usize) -> Cell {
+    let (col, row) = (i % size.x as usize, i / size.x as usize);
+    layout.cell_at([
+        chunk.x * size.x as i32 + col as i32,
+        chunk.y * size.y as i32 + row as i32,
+    ])
+}
+
+impl<T: Default> GridStorage<T> for ChunkedStorage<T> {
+    fn get(&self, cell: Cell) -> Option<&T> {
+        self.get(cell)
+    }
+
+    fn get_mut(&mut self, cell: Cell) -> Option<&mut T> {
+        self.get_mut(cell)
+    }
+
+    fn set(&mut self, cell: Cell, data: T) -> Option<T> {
+        self.set(cell, data)
+    }
+
+    fn contains(&self, cell: Cell) -> bool {
+        self.is_loaded(self.chunk_of(cell))
+    }
+
+    fn iter<'a>(&'a self) -> impl Iterator<Item = (Cell, &'a T)>
+    where
+        T: 'a,
+    {
+        self.iter()
+    }
+
+    fn iter_mut<'a>(&'a mut self) -> impl Iterator<Item = (Cell, &'a mut T)>
+    where
+        T: 'a,
+    {
+        self.iter_mut()
+    }
+
+    fn dimensions(&self) -> UVec2 {
+        self.dimensions()
+    }
+
+    /// Loads the chunk containing the cell if needed, so this never fails
+    fn try_set(&mut self, cell: Cell, data: T) -> Result<Option<T>, LettucesError> {
+        Ok(self.set(cell, data))
+    }
+}
+
+impl<T: Debug> Debug for ChunkedStorage<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ChunkedStorage")
+            .field("chunks", &self.chunks)
+            .field("chunk_size", &self.chunk_size)
+            .field("layout", &self.layout)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use glam::UVec2;
+
+    use crate::cell::Cell;
+
+    use super::{ChunkLayout, ChunkedStorage};
+
+    #[test]
+    fn test_negative_chunks() {
+        let mut map = ChunkedStorage::<u32>::new(UVec2::new(4, 4), ChunkLayout::Square);
+
+        assert_eq!(map.chunk_of(Cell::new(0, 0)), Cell::new(0, 0));
+        assert_eq!(map.chunk_of(Cell::new(3, 3)), Cell::new(0, 0));
+        assert_eq!(map.chunk_of(Cell::new(-1, 0)), Cell::new(-1, 0));
+        assert_eq!(map.chunk_of(Cell::new(-4, -5)), Cell::new(-1, -2));
+
+        assert!(map.get(Cell::new(-1, -1)).is_none());
+        assert_eq!(map.set(Cell::new(-1, -1), 5), Some(0));
+        assert_eq!(map.get(Cell::new(-1, -1)), Some(&5));
+        assert_eq!(map.get(Cell::new(-4, -4)), Some(&0));
+        assert!(map.get(Cell::new(0, 0)).is_none());
+        assert_eq!(map.chunks().count(), 1);
+        assert_eq!(map.dimensions(), UVec2::new(4, 4));
+
+        let cells: Vec<Cell> = map.chunk_cells(Cell::new(-1, -1)).collect();
+        assert_eq!(cells.len(), 16);
+        assert!(cells.iter().all(|cell| map.get(*cell).is_some()));
+        assert!(map
+            .iter()
+            .any(|(cell, t)| cell == Cell::new(-1, -1) && *t == 5));
+
+        // Chunks at both ends of the plane span more cells than a u32 holds
+        map.set(Cell::new(i32::MAX, i32::MAX), 1);
+        map.set(Cell::new(i32::MIN, i32::MIN), 1);
+        assert_eq!(map.dimensions(), UVec2::new(u32::MAX, u32::MAX));
+    }
+
+    #[test]
+    fn test_hooks_and_dirty() {
+        let saved = Arc::new(Mutex::new(Vec::new()));
+        let saved_hook = saved.clone();
+        let mut map = ChunkedStorage::<u32>::new(UVec2::new(2, 3), ChunkLayout::Square)
+            .with_load_hook(|chunk, data| data.grid.fill(chunk.x.unsigned_abs()))
+            .with_unload_hook(move |chunk, data| {
+                saved_hook.lock().unwrap().push((chunk, data.dirty))
+            });
+
+        map.load_chunk(Cell::new(-2, 0));
+        map.load_chunk(Cell::new(1, 0));
+        assert_eq!(map.get(Cell::new(-3, 2)), Some(&2));
+        assert_eq!(map.dirty_chunks().count(), 0);
+
+        map.set(Cell::new(2, 0), 9);
+        assert_eq!(
+            map.dirty_chunks().collect::<Vec<_>>(),
+            vec![Cell::new(1, 0)]
+        );
+        assert!(map.unload_chunk(Cell::new(1, 0)));
+        assert!(!map.unload_chunk(Cell::new(1, 0)));
+        map.unload_where(|_, _| false);
+
+        let mut saved = saved.lock().unwrap().clone();
+        saved.sort_by_key(|(chunk, _)| chunk.x);
+        assert_eq!(
+            saved,
+            vec![(Cell::new(-2, 0), false), (Cell::new(1, 0), true)]
+        );
+
+        map.set(Cell::new(0, 0), 1);
+        assert_eq!(map.take_dirty(), vec![Cell::new(0, 0)]);
+        assert_eq!(map.dirty_chunks().count(), 0);
+    }
+
+    #[cfg(feature = "hex")]
+    #[test]
+    fn test_hex_chunks() {
+        use hexx::OffsetHexMode;
+
+        let mut map =
+            ChunkedStorage::<u32>::new(UVec2::new(3, 3), ChunkLayout::Hex(OffsetHexMode::OddRows));
+        for chunk in [Cell::new(0, 0), Cell::new(-1, 2), Cell::new(1, -1)] {
+            let cells: Vec<Cell> = map.chunk_cells(chunk).collect();
+            assert!(cells.iter().all(|cell| map.chunk_of(*cell) == chunk));
+            map.load_chunk(chunk);
+        }
+
+        let mut cells: Vec<Cell> = map.iter().map(|(cell, _)| cell).collect();
+        cells.sort_by_key(|cell| (cell.x, cell.y));
+        cells.dedup();
+        assert_eq!(cells.len(), 27);
+        assert!(cells.iter().all(|cell| map.get(*cell).is_some()));
+    }
+}
@@ -48,6 +48,16 @@ use core::slice::IterMut;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
+use glam::UVec2;
+
+pub use crate::cell::transform::GridTransform;
+use crate::{cell::Cell, error::LettucesError};
+
+use super::{
+    view::{GridView, GridViewMut},
+    GridStorage,
+};
+
 #[doc(hidden)]
 #[macro_export]
 macro_rules! count {
@@ -123,7 +133,7 @@ macro_rules! grid {
 /// The size limit of a grid is `rows * cols < usize`.
 ///
 /// The grid data is stored in a row-major memory layout.
-#[derive(Hash)]
+#[derive(Hash, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "bevy_reflect", derive(Reflect))]
 pub struct Grid<T> {
@@ -238,6 +248,29 @@ impl<T> Grid<T> {
         }
     }
 
+    /// Returns a grid from a vector with a given column length, or [`LettucesError::SizeMismatch`] if the length of
+    /// `vec` isn't a multiple of `cols`.
+    ///
+    /// ```
+    /// use lettuces::storage::grid::*;
+    /// use lettuces::error::LettucesError;
+    /// assert!(Grid::try_from_vec(vec![1,2,3,4,5,6], 3).is_ok());
+    /// assert_eq!(
+    ///     Grid::try_from_vec(vec![1,2,3,4,5], 3),
+    ///     Err(LettucesError::SizeMismatch { expected: 6, found: 5 })
+    /// );
+    /// ```
+    pub fn try_from_vec(vec: Vec<T>, cols: usize) -> Result<Grid<T>, LettucesError> {
+        let expected = vec.len().checked_next_multiple_of(cols).unwrap_or(0);
+        if expected != vec.len() {
+            return Err(LettucesError::SizeMismatch {
+                expected,
+                found: vec.len(),
+            });
+        }
+        Ok(Grid::from_vec(vec, cols))
+    }
+
     /// Returns a reference to an element, without performing bound checks.
     /// Generally not recommended, use with caution!
     ///
@@ -335,7 +368,7 @@ impl<T> Grid<T> {
     /// assert_eq!(iter.next(), Some(&4));
     /// assert_eq!(iter.next(), None);
     /// ```
-    pub fn iter(&self) -> Iter<T> {
+    pub fn iter(&self) -> Iter<'_, T> {
         self.data.iter()
     }
 
@@ -349,7 +382,7 @@ impl<T> Grid<T> {
     /// assert_eq!(next, Some(&mut 1));
     /// *next.unwrap() = 10;
     /// ```
-    pub fn iter_mut(&mut self) -> IterMut<T> {
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
         self.data.iter_mut()
     }
 
@@ -370,7 +403,7 @@ impl<T> Grid<T> {
     /// # Panics
     ///
     /// Panics if the col index is out of bounds.
-    pub fn iter_col(&self, col: usize) -> StepBy<Iter<T>> {
+    pub fn iter_col(&self, col: usize) -> StepBy<Iter<'_, T>> {
         if col < self.cols {
             return self.data[col..].iter().step_by(self.cols);
         }
@@ -398,7 +431,7 @@ impl<T> Grid<T> {
     /// # Panics
     ///
     /// Panics if the col index is out of bounds.
-    pub fn iter_col_mut(&mut self, col: usize) -> StepBy<IterMut<T>> {
+    pub fn iter_col_mut(&mut self, col: usize) -> StepBy<IterMut<'_, T>> {
         let cols = self.cols;
         if col < cols {
             return self.data[col..].iter_mut().step_by(cols);
@@ -427,7 +460,7 @@ impl<T> Grid<T> {
     /// # Panics
     ///
     /// Panics if the row index is out of bounds.
-    pub fn iter_row(&self, row: usize) -> Iter<T> {
+    pub fn iter_row(&self, row: usize) -> Iter<'_, T> {
         if row < self.rows {
             let start = row * self.cols;
             self.data[start..(start + self.cols)].iter()
@@ -456,7 +489,7 @@ impl<T> Grid<T> {
     /// # Panics
     ///
     /// Panics if the row index is out of bounds.
-    pub fn iter_row_mut(&mut self, row: usize) -> IterMut<T> {
+    pub fn iter_row_mut(&mut self, row: usize) -> IterMut<'_, T> {
         if row < self.rows {
             let cols = self.cols;
             let start = row * cols;
@@ -516,6 +549,28 @@ impl<T> Grid<T> {
         }
     }
 
+    /// Add a new row to the grid, or return an error instead of panicking:
+    ///  - [`LettucesError::Empty`] if `row.len() == 0`
+    ///  - [`LettucesError::SizeMismatch`] if the grid is not empty and `row.len() != grid.cols()`
+    ///
+    /// ```
+    /// use lettuces::storage::grid::*;
+    /// use lettuces::error::LettucesError;
+    /// use lettuces::grid;
+    /// let mut grid: Grid<u8> = grid![[1, 2, 3][3, 4, 5]];
+    /// assert_eq!(
+    ///     grid.try_push_row(vec![6, 7]),
+    ///     Err(LettucesError::SizeMismatch { expected: 3, found: 2 })
+    /// );
+    /// assert_eq!(grid.try_push_row(vec![]), Err(LettucesError::Empty));
+    /// assert!(grid.try_push_row(vec![6, 7, 8]).is_ok());
+    /// ```
+    pub fn try_push_row(&mut self, row: Vec<T>) -> Result<(), LettucesError> {
+        Self::check_line(row.len(), self.rows, self.cols)?;
+        self.push_row(row);
+        Ok(())
+    }
+
     /// Add a new column to the grid.
     ///
     /// *Important:*
@@ -570,6 +625,15 @@ impl<T> Grid<T> {
         }
     }
 
+    /// Add a new column to the grid, or return an error instead of panicking:
+    ///  - [`LettucesError::Empty`] if `col.len() == 0`
+    ///  - [`LettucesError::SizeMismatch`] if the grid is not empty and `col.len() != grid.rows()`
+    pub fn try_push_col(&mut self, col: Vec<T>) -> Result<(), LettucesError> {
+        Self::check_line(col.len(), self.cols, self.rows)?;
+        self.push_col(col);
+        Ok(())
+    }
+
     /// Removes the last row from a grid and returns it, or None if it is empty.
     ///
     /// # Examples
@@ -715,11 +779,24 @@ impl<T> Grid<T> {
             self.rows
         );
         let data_idx = index * input_len;
-        self.data.splice(data_idx..data_idx, row.into_iter());
+        self.data.splice(data_idx..data_idx, row);
         self.cols = input_len;
         self.rows += 1;
     }
 
+    /// Insert a new row at the index, or return an error instead of panicking:
+    ///  - [`LettucesError::Empty`] if `row.len() == 0`
+    ///  - [`LettucesError::SizeMismatch`] if the grid is not empty and `row.len() != grid.cols()`
+    ///  - [`LettucesError::OutOfBounds`] if the index is greater than the number of rows
+    pub fn try_insert_row(&mut self, index: usize, row: Vec<T>) -> Result<(), LettucesError> {
+        Self::check_line(row.len(), self.rows, self.cols)?;
+        if index > self.rows {
+            return Err(LettucesError::OutOfBounds(Cell::new(0, index as i32)));
+        }
+        self.insert_row(index, row);
+        Ok(())
+    }
+
     /// Insert a new column at the index.
     ///
     /// Important! Insertion of columns is a lot slower than the lines insertion.
@@ -763,6 +840,33 @@ impl<T> Grid<T> {
         self.cols += 1;
     }
 
+    /// Insert a new column at the index, or return an error instead of panicking:
+    ///  - [`LettucesError::Empty`] if `col.len() == 0`
+    ///  - [`LettucesError::SizeMismatch`] if the grid is not empty and `col.len() != grid.rows()`
+    ///  - [`LettucesError::OutOfBounds`] if the index is greater than the number of columns
+    pub fn try_insert_col(&mut self, index: usize, col: Vec<T>) -> Result<(), LettucesError> {
+        Self::check_line(col.len(), self.cols, self.rows)?;
+        if index > self.cols {
+            return Err(LettucesError::OutOfBounds(Cell::new(index as i32, 0)));
+        }
+        self.insert_col(index, col);
+        Ok(())
+    }
+
+    /// Checks that a row or column of `len` elements can be added to a grid with `count` lines of `line_len` elements
+    fn check_line(len: usize, count: usize, line_len: usize) -> Result<(), LettucesError> {
+        if len == 0 {
+            return Err(LettucesError::Empty);
+        }
+        if count > 0 && len != line_len {
+            return Err(LettucesError::SizeMismatch {
+                expected: line_len,
+                found: len,
+            });
+        }
+        Ok(())
+    }
+
     /// Returns a reference to the internal data structure of the grid.
     ///
     /// Grid uses a row major layout.
@@ -806,6 +910,194 @@ impl<T> Grid<T> {
         }
     }
 
+    /// Applies the transform to the grid in place, swapping every element along the cycles of the transform without
+    /// cloning it.
+    ///
+    /// The element at a cell `cell` ends up at `transform.apply(cell, size)`, where `size` is the number of columns
+    /// (x) and rows (y) before the transform.
+    ///
+    /// # Examples
+    /// ```
+    /// use glam::UVec2;
+    /// use lettuces::cell::Cell;
+    /// use lettuces::storage::{grid::*, GridStorage};
+    /// use lettuces::grid;
+    /// let grid = grid![[1,2,3,4][5,6,7,8][9,10,11,12]];
+    /// let size = UVec2::new(4, 3);
+    /// for transform in [
+    ///     GridTransform::RotateCw,
+    ///     GridTransform::RotateCcw,
+    ///     GridTransform::Rotate180,
+    ///     GridTransform::FlipHorizontal,
+    ///     GridTransform::FlipVertical,
+    /// ] {
+    ///     let mut rotated = grid.clone();
+    ///     rotated.transform(transform);
+    ///     assert_eq!(rotated, grid.transformed(transform));
+    ///     for (cell, data) in GridStorage::iter(&grid) {
+    ///         assert_eq!(GridStorage::get(&rotated, transform.apply(cell, size)), Some(data));
+    ///     }
+    /// }
+    /// ```
+    pub fn transform(&mut self, transform: GridTransform) {
+        let old_size = UVec2::new(self.cols as u32, self.rows as u32);
+        let new_size = transform.transformed_size(old_size);
+        // Each position is filled from its source, and the source is then filled from its own source, until the cycle
+        // arrives back at the first position whose element has been carried along by the swaps
+        let mut placed = vec![false; self.data.len()];
+        for start in 0..self.data.len() {
+            let mut current = start;
+            while !placed[current] {
+                placed[current] = true;
+                let source = source_index(transform, current, old_size, new_size);
+                if source == start {
+                    break;
+                }
+                self.data.swap(current, source);
+                current = source;
+            }
+        }
+        self.cols = new_size.x as usize;
+        self.rows = new_size.y as usize;
+    }
+
+    /// Returns a copy of the grid with the transform applied. See [`Grid::transform`]
+    #[must_use]
+    pub fn transformed(&self, transform: GridTransform) -> Grid<T>
+    where
+        T: Clone,
+    {
+        let old_size = UVec2::new(self.cols as u32, self.rows as u32);
+        let new_size = transform.transformed_size(old_size);
+        Grid {
+            data: (0..self.data.len())
+                .map(|i| self.data[source_index(transform, i, old_size, new_size)].clone())
+                .collect(),
+            cols: new_size.x as usize,
+            rows: new_size.y as usize,
+        }
+    }
+
+    /// Rotates the grid a quarter turn clockwise in place, so the first column becomes the first row in reverse.
+    ///
+    /// # Examples
+    /// ```
+    /// use lettuces::storage::grid::Grid;
+    /// use lettuces::grid;
+    /// let mut grid = grid![[1,2,3][4,5,6]];
+    /// grid.rotate_cw();
+    /// assert_eq!(grid, grid![[4,1][5,2][6,3]]);
+    /// ```
+    pub fn rotate_cw(&mut self) {
+        self.transform(GridTransform::RotateCw);
+    }
+
+    /// Returns a copy of the grid rotated a quarter turn clockwise. See [`Grid::rotate_cw`]
+    #[must_use]
+    pub fn rotated_cw(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        self.transformed(GridTransform::RotateCw)
+    }
+
+    /// Rotates the grid a quarter turn counter clockwise in place, so the last column becomes the first row.
+    ///
+    /// # Examples
+    /// ```
+    /// use lettuces::storage::grid::Grid;
+    /// use lettuces::grid;
+    /// let mut grid = grid![[1,2,3][4,5,6]];
+    /// grid.rotate_ccw();
+    /// assert_eq!(grid, grid![[3,6][2,5][1,4]]);
+    /// ```
+    pub fn rotate_ccw(&mut self) {
+        self.transform(GridTransform::RotateCcw);
+    }
+
+    /// Returns a copy of the grid rotated a quarter turn counter clockwise. See [`Grid::rotate_ccw`]
+    #[must_use]
+    pub fn rotated_ccw(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        self.transformed(GridTransform::RotateCcw)
+    }
+
+    /// Rotates the grid a half turn in place.
+    ///
+    /// # Examples
+    /// ```
+    /// use lettuces::storage::grid::Grid;
+    /// use lettuces::grid;
+    /// let mut grid = grid![[1,2,3][4,5,6]];
+    /// grid.rotate_180();
+    /// assert_eq!(grid, grid![[6,5,4][3,2,1]]);
+    /// ```
+    pub fn rotate_180(&mut self) {
+        self.data.reverse();
+    }
+
+    /// Returns a copy of the grid rotated a half turn. See [`Grid::rotate_180`]
+    #[must_use]
+    pub fn rotated_180(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        self.transformed(GridTransform::Rotate180)
+    }
+
+    /// Mirrors the grid in place so each row is reversed.
+    ///
+    /// # Examples
+    /// ```
+    /// use lettuces::storage::grid::Grid;
+    /// use lettuces::grid;
+    /// let mut grid = grid![[1,2,3][4,5,6]];
+    /// grid.flip_horizontal();
+    /// assert_eq!(grid, grid![[3,2,1][6,5,4]]);
+    /// ```
+    pub fn flip_horizontal(&mut self) {
+        if self.cols == 0 {
+            return;
+        }
+        for row in self.data.chunks_exact_mut(self.cols) {
+            row.reverse();
+        }
+    }
+
+    /// Returns a copy of the grid with each row reversed. See [`Grid::flip_horizontal`]
+    #[must_use]
+    pub fn flipped_horizontal(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        self.transformed(GridTransform::FlipHorizontal)
+    }
+
+    /// Mirrors the grid in place so the order of the rows is reversed.
+    ///
+    /// # Examples
+    /// ```
+    /// use lettuces::storage::grid::Grid;
+    /// use lettuces::grid;
+    /// let mut grid = grid![[1,2,3][4,5,6]];
+    /// grid.flip_vertical();
+    /// assert_eq!(grid, grid![[4,5,6][1,2,3]]);
+    /// ```
+    pub fn flip_vertical(&mut self) {
+        self.transform(GridTransform::FlipVertical);
+    }
+
+    /// Returns a copy of the grid with the order of the rows reversed. See [`Grid::flip_vertical`]
+    #[must_use]
+    pub fn flipped_vertical(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        self.transformed(GridTransform::FlipVertical)
+    }
+
     /// Fills the grid with elements by cloning `value`.
     ///
     /// # Examples
@@ -896,6 +1188,309 @@ impl<T> Grid<T> {
             col_index: 0,
         }
     }
+
+    /// Returns a view of the whole grid. See [`Grid::view`]
+    #[must_use]
+    pub fn as_view(&self) -> GridView<'_, T> {
+        GridView::new(&self.data, self.cols, self.rows, self.cols)
+    }
+
+    /// Returns a mutable view of the whole grid. See [`Grid::view_mut`]
+    #[must_use]
+    pub fn as_view_mut(&mut self) -> GridViewMut<'_, T> {
+        let (rows, cols) = (self.rows, self.cols);
+        self.view_mut(0, 0, rows, cols).unwrap()
+    }
+
+    /// Returns a view of the region starting at `(row, col)` with the given number of rows and columns. The view is
+    /// indexed locally so `(0, 0)` is the element at `(row, col)` in the grid. Returns None if the region does not fit
+    /// inside the grid.
+    ///
+    /// # Examples
+    /// ```
+    /// use lettuces::storage::grid::Grid;
+    /// use lettuces::grid;
+    /// let grid = grid![[1,2,3][4,5,6][7,8,9]];
+    /// let view = grid.view(1, 1, 2, 2).unwrap();
+    /// assert_eq!(view[(0, 0)], 5);
+    /// assert_eq!(view.row(1), Some(&[8, 9][..]));
+    /// assert!(grid.view(2, 2, 2, 1).is_none());
+    /// ```
+    #[must_use]
+    pub fn view(
+        &self,
+        row: usize,
+        col: usize,
+        rows: usize,
+        cols: usize,
+    ) -> Option<GridView<'_, T>> {
+        self.as_view().view(row, col, rows, cols)
+    }
+
+    /// Returns a mutable view of the region starting at `(row, col)` with the given number of rows and columns. See
+    /// [`Grid::view`]
+    ///
+    /// # Examples
+    /// ```
+    /// use lettuces::storage::grid::Grid;
+    /// use lettuces::grid;
+    /// let mut grid = grid![[1,2,3][4,5,6][7,8,9]];
+    /// let mut view = grid.view_mut(1, 1, 2, 2).unwrap();
+    /// view.fill(0);
+    /// view[(0, 1)] = 10;
+    /// assert_eq!(grid, grid![[1,2,3][4,0,10][7,0,0]]);
+    /// ```
+    #[must_use]
+    pub fn view_mut(
+        &mut self,
+        row: usize,
+        col: usize,
+        rows: usize,
+        cols: usize,
+    ) -> Option<GridViewMut<'_, T>> {
+        if row.checked_add(rows)? > self.rows || col.checked_add(cols)? > self.cols {
+            return None;
+        }
+        if rows == 0 || cols == 0 {
+            return Some(GridViewMut::new(&mut [], 0, 0, 0));
+        }
+        let start = row * self.cols + col;
+        Some(GridViewMut::new(
+            &mut self.data[start..],
+            self.cols,
+            rows,
+            cols,
+        ))
+    }
+
+    /// Splits the grid into two disjoint mutable views at the given row. The first view holds the rows before `row`.
+    ///
+    /// # Panics
+    /// If `row > rows`
+    ///
+    /// # Examples
+    /// ```
+    /// use lettuces::storage::grid::Grid;
+    /// use lettuces::grid;
+    /// let mut grid = grid![[1,2][3,4][5,6]];
+    /// let (mut top, mut bottom) = grid.split_at_row(1);
+    /// top.fill(0);
+    /// bottom[(1, 1)] = 60;
+    /// assert_eq!(grid, grid![[0,0][3,4][5,60]]);
+    /// ```
+    #[must_use]
+    pub fn split_at_row(&mut self, row: usize) -> (GridViewMut<'_, T>, GridViewMut<'_, T>) {
+        self.as_view_mut().split_at_row(row)
+    }
+
+    /// Splits the grid into two disjoint mutable views at the given column. The first view holds the columns before
+    /// `col`.
+    ///
+    /// # Panics
+    /// If `col > cols`
+    ///
+    /// # Examples
+    /// ```
+    /// use lettuces::storage::grid::Grid;
+    /// use lettuces::grid;
+    /// let mut grid = grid![[1,2,3][4,5,6]];
+    /// let (mut left, mut right) = grid.split_at_col(2);
+    /// left[(1, 0)] = 40;
+    /// right.fill(0);
+    /// assert_eq!(grid, grid![[1,2,0][40,5,0]]);
+    /// ```
+    #[must_use]
+    pub fn split_at_col(&mut self, col: usize) -> (GridViewMut<'_, T>, GridViewMut<'_, T>) {
+        self.as_view_mut().split_at_col(col)
+    }
+
+    /// Copies `src` into the grid with its top left element at `(row, col)`. Elements that land outside the grid are
+    /// skipped, so `row` and `col` may be negative or place `src` partly past the bottom right border.
+    ///
+    /// # Examples
+    /// ```
+    /// use lettuces::storage::grid::Grid;
+    /// use lettuces::grid;
+    /// let mut grid = grid![[0,0,0][0,0,0][0,0,0]];
+    /// grid.blit(&grid![[1,2][3,4]], 2, -1);
+    /// assert_eq!(grid, grid![[0,0,0][0,0,0][2,0,0]]);
+    /// ```
+    pub fn blit(&mut self, src: &Grid<T>, row: isize, col: isize)
+    where
+        T: Clone,
+    {
+        self.blit_with(src, row, col, |t, data| t.clone_from(data));
+    }
+
+    /// Copies `src` into the grid like [`Grid::blit`], skipping the elements of `src` that are None so the data below
+    /// them is kept.
+    ///
+    /// # Examples
+    /// ```
+    /// use lettuces::storage::grid::Grid;
+    /// use lettuces::grid;
+    /// let mut grid = grid![[0,0,0][0,0,0]];
+    /// grid.blit_masked(&grid![[Some(1),None][None,Some(4)]], 0, 1);
+    /// assert_eq!(grid, grid![[0,1,0][0,0,4]]);
+    /// ```
+    pub fn blit_masked(&mut self, src: &Grid<Option<T>>, row: isize, col: isize)
+    where
+        T: Clone,
+    {
+        self.blit_with(src, row, col, |t, data| {
+            if let Some(data) = data {
+                t.clone_from(data);
+            }
+        });
+    }
+
+    /// Places `src` over the grid with its top left element at `(row, col)` and calls `f` with every overlapping pair
+    /// of elements. Use this for custom transparency or blending. Elements that land outside the grid are skipped.
+    ///
+    /// # Examples
+    /// ```
+    /// use lettuces::storage::grid::Grid;
+    /// use lettuces::grid;
+    /// let mut grid = grid![[1,1,1][1,1,1]];
+    /// grid.blit_with(&grid![[5,0][0,5]], -1, 1, |t, data| {
+    ///     if *data != 0 {
+    ///         *t += data;
+    ///     }
+    /// });
+    /// assert_eq!(grid, grid![[1,1,6][1,1,1]]);
+    /// ```
+    pub fn blit_with<U>(
+        &mut self,
+        src: &Grid<U>,
+        row: isize,
+        col: isize,
+        mut f: impl FnMut(&mut T, &U),
+    ) {
+        let Some(rows) = clip(row, src.rows, self.rows) else {
+            return;
+        };
+        let Some(cols) = clip(col, src.cols, self.cols) else {
+            return;
+        };
+        let src = src.view(rows.src, cols.src, rows.len, cols.len).unwrap();
+        let mut dst = self
+            .view_mut(rows.dst, cols.dst, rows.len, cols.len)
+            .unwrap();
+        for (dst_row, src_row) in dst.iter_rows_mut().zip(src.iter_rows()) {
+            for (t, data) in dst_row.iter_mut().zip(src_row) {
+                f(t, data);
+            }
+        }
+    }
+
+    /// Returns a copy of the region starting at `(row, col)` with the given number of rows and columns. The region is
+    /// clipped to the grid, so the copy is smaller when the region crosses a border and empty when it misses the grid.
+    ///
+    /// # Examples
+    /// ```
+    /// use lettuces::storage::grid::Grid;
+    /// use lettuces::grid;
+    /// let grid = grid![[1,2,3][4,5,6][7,8,9]];
+    /// assert_eq!(grid.copy_region(1, 0, 2, 2), grid![[4,5][7,8]]);
+    /// assert_eq!(grid.copy_region(-1, 2, 3, 3), grid![[3][6]]);
+    /// assert!(grid.copy_region(3, 0, 1, 1).is_empty());
+    /// ```
+    #[must_use]
+    pub fn copy_region(&self, row: isize, col: isize, rows: usize, cols: usize) -> Grid<T>
+    where
+        T: Clone,
+    {
+        match (clip(row, rows, self.rows), clip(col, cols, self.cols)) {
+            (Some(rows), Some(cols)) => self
+                .view(rows.dst, cols.dst, rows.len, cols.len)
+                .unwrap()
+                .to_grid(),
+            _ => Grid::from_vec(Vec::new(), 0),
+        }
+    }
+}
+
+/// The overlap along one axis of a region of `len` elements placed at `start` with a grid of `bound` elements
+struct ClippedAxis {
+    /// Index of the first overlapping element in the grid
+    dst: usize,
+    /// Index of the first overlapping element in the region
+    src: usize,
+    len: usize,
+}
+
+/// Clips a region along one axis. Returns None if the region does not overlap the grid
+fn clip(start: isize, len: usize, bound: usize) -> Option<ClippedAxis> {
+    let end = start.saturating_add_unsigned(len).min(bound as isize);
+    let first = start.max(0);
+    if end <= first {
+        return None;
+    }
+    Some(ClippedAxis {
+        dst: first as usize,
+        src: first.abs_diff(start),
+        len: (end - first) as usize,
+    })
+}
+
+/// Cells map to grid positions with `x` as the column and `y` as the row
+/// The index in the original data of the element that ends up at index `i` after the transform
+fn source_index(transform: GridTransform, i: usize, old_size: UVec2, new_size: UVec2) -> usize {
+    let cols = new_size.x as usize;
+    let target = Cell::new((i % cols) as i32, (i / cols) as i32);
+    let source = transform.inverse().apply(target, new_size);
+    source.y as usize * old_size.x as usize + source.x as usize
+}
+
+impl<T> GridStorage<T> for Grid<T> {
+    fn get(&self, cell: Cell) -> Option<&T> {
+        if cell.x.is_negative() || cell.y.is_negative() {
+            return None;
+        }
+        self.get(cell.y as usize, cell.x as usize)
+    }
+
+    fn get_mut(&mut self, cell: Cell) -> Option<&mut T> {
+        if cell.x.is_negative() || cell.y.is_negative() {
+            return None;
+        }
+        self.get_mut(cell.y as usize, cell.x as usize)
+    }
+
+    fn set(&mut self, cell: Cell, data: T) -> Option<T> {
+        let t = GridStorage::get_mut(self, cell)?;
+        Some(std::mem::replace(t, data))
+    }
+
+    fn contains(&self, cell: Cell) -> bool {
+        GridStorage::get(self, cell).is_some()
+    }
+
+    fn iter<'a>(&'a self) -> impl Iterator<Item = (Cell, &'a T)>
+    where
+        T: 'a,
+    {
+        let cols = self.cols;
+        self.data
+            .iter()
+            .enumerate()
+            .map(move |(i, t)| (Cell::new((i % cols) as i32, (i / cols) as i32), t))
+    }
+
+    fn iter_mut<'a>(&'a mut self) -> impl Iterator<Item = (Cell, &'a mut T)>
+    where
+        T: 'a,
+    {
+        let cols = self.cols;
+        self.data
+            .iter_mut()
+            .enumerate()
+            .map(move |(i, t)| (Cell::new((i % cols) as i32, (i / cols) as i32), t))
+    }
+
+    fn dimensions(&self) -> UVec2 {
+        UVec2::new(self.cols as u32, self.rows as u32)
+    }
 }
 
 impl<T: Clone> Clone for Grid<T> {
@@ -1004,14 +1599,6 @@ impl<T: fmt::Debug> fmt::Debug for Grid<T> {
     }
 }
 
-impl<T: Eq> PartialEq for Grid<T> {
-    fn eq(&self, other: &Self) -> bool {
-        self.rows == other.rows && self.cols == other.cols && self.data == other.data
-    }
-}
-
-impl<T: Eq> Eq for Grid<T> {}
-
 pub struct GridRowIter<'a, T> {
     grid: &'a Grid<T>,
     row_index: usize,
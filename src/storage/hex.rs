@@ -1,19 +1,39 @@
 use glam::UVec2;
 
-use hexx::HexOrientation;
+use hexx::{Hex, HexOrientation, OffsetHexMode};
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
 #[cfg(feature = "bevy_reflect")]
 use bevy::prelude::Reflect;
 
-use crate::cell::Cell;
+use crate::{
+    cell::{hex::HexTransform, Cell},
+    error::{row_length, LettucesError},
+};
 
-use super::grid::Grid;
+use super::{grid::Grid, sparse::SparseStorage, GridStorage};
+
+/// Which rows or columns of a [`HexRectangleStorage`] are shoved over to line up the hexagons
+#[derive(Hash, Clone, Copy, Default, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bevy_reflect", derive(Reflect))]
+pub enum OffsetParity {
+    /// Odd rows are shoved right for pointy hexagons, odd columns are shoved down for flat hexagons
+    #[default]
+    Odd,
+    /// Even rows are shoved right for pointy hexagons, even columns are shoved down for flat hexagons
+    Even,
+}
 
 /// Storage structure for a hexagon map that is rectangular in nature with rows of the same length.
 ///
-/// Uses Axial Coordinate System
+/// Uses Axial Coordinate System. The grid is indexed by the offset coordinates of each cell, so `orientation` and the
+/// [`OffsetParity`] together pick the [`OffsetHexMode`] used. Defaults to odd rows for pointy hexagons and odd columns
+/// for flat hexagons, see [`HexRectangleStorage::new_with_mode`] to use another mode
+///
+/// [`HexRectangleStorage::parity`] is which rows or columns are shoved over and [`HexRectangleStorage::offset_mode`]
+/// is the mode it forms with the orientation. Build the storage with one of its constructors
 ///
 /// See [RedBlobGames Hexagon Map Storage](https://www.redblobgames.com/grids/hexagons/#map-storage)
 #[derive(Hash, Clone, Debug)]
@@ -22,6 +42,9 @@ use super::grid::Grid;
 pub struct HexRectangleStorage<T> {
     pub grid: Grid<T>,
     pub orientation: HexOrientation,
+    /// Which rows or columns are shoved over. Defaults to odd when missing from saved data
+    #[cfg_attr(feature = "serde", serde(default))]
+    parity: OffsetParity,
 }
 
 impl<T> HexRectangleStorage<T> {
@@ -30,10 +53,19 @@ impl<T> HexRectangleStorage<T> {
     where
         T: Default,
     {
-        HexRectangleStorage {
-            grid: Grid::new(y_size, x_size),
-            orientation,
-        }
+        HexRectangleStorage::new_with_mode(x_size, y_size, default_mode(orientation))
+    }
+
+    /// Construct a new [`HexRectangleStorage`] from the default for the given data, indexed with the given offset mode
+    pub fn new_with_mode(
+        x_size: usize,
+        y_size: usize,
+        mode: OffsetHexMode,
+    ) -> HexRectangleStorage<T>
+    where
+        T: Default,
+    {
+        HexRectangleStorage::from_grid(Grid::new(y_size, x_size), mode)
     }
 
     pub fn dimensions(&self) -> UVec2 {
@@ -53,102 +85,826 @@ impl<T> HexRectangleStorage<T> {
     where
         T: Clone,
     {
+        HexRectangleStorage::from_grid(Grid::init(y_size, x_size, data), default_mode(orientation))
+    }
+
+    /// Construct a new [`HexRectangleStorage`] from a vec of vecs of data.
+    ///
+    /// Each inner vec is a row of the map indexed by offset coordinates, so `data[row][col]` is stored at
+    /// `Cell::from_offset_coordinates([col, row], mode)`. Use [`HexRectangleStorage::from_vec_with_mode`] to load
+    /// data using a different offset mode without re-indexing it
+    ///
+    /// # Panics
+    /// If the rows have different lengths. See [`HexRectangleStorage::try_from_vec`]
+    pub fn new_from_vec(data: Vec<Vec<T>>, orientation: HexOrientation) -> HexRectangleStorage<T> {
+        HexRectangleStorage::from_vec_with_mode(data, default_mode(orientation))
+    }
+
+    /// Construct a new [`HexRectangleStorage`] from a vec of vecs of data indexed with the given offset mode, so
+    /// `data[row][col]` is stored at `Cell::from_offset_coordinates([col, row], mode)`
+    ///
+    /// # Panics
+    /// If the rows have different lengths. See [`HexRectangleStorage::try_from_vec_with_mode`]
+    pub fn from_vec_with_mode(data: Vec<Vec<T>>, mode: OffsetHexMode) -> HexRectangleStorage<T> {
+        let row_length = row_length(&data).unwrap_or_else(|error| panic!("{error}"));
+        HexRectangleStorage::from_grid(
+            Grid::from_vec(data.into_iter().flatten().collect(), row_length),
+            mode,
+        )
+    }
+
+    /// Construct a new [`HexRectangleStorage`] from a vec of vecs of data like
+    /// [`HexRectangleStorage::new_from_vec`]. Returns [`LettucesError::Empty`] if there is no data or
+    /// [`LettucesError::Ragged`] if the rows have different lengths
+    pub fn try_from_vec(
+        data: Vec<Vec<T>>,
+        orientation: HexOrientation,
+    ) -> Result<HexRectangleStorage<T>, LettucesError> {
+        HexRectangleStorage::try_from_vec_with_mode(data, default_mode(orientation))
+    }
+
+    /// Construct a new [`HexRectangleStorage`] from a vec of vecs of data like
+    /// [`HexRectangleStorage::from_vec_with_mode`]. Returns [`LettucesError::Empty`] if there is no data or
+    /// [`LettucesError::Ragged`] if the rows have different lengths
+    pub fn try_from_vec_with_mode(
+        data: Vec<Vec<T>>,
+        mode: OffsetHexMode,
+    ) -> Result<HexRectangleStorage<T>, LettucesError> {
+        if row_length(&data)? == 0 {
+            return Err(LettucesError::Empty);
+        }
+        Ok(HexRectangleStorage::from_vec_with_mode(data, mode))
+    }
+
+    /// Sets which rows or columns are shoved over, keeping the orientation. The data stays in place, so each grid
+    /// position keeps its data but is accessed at the cell for the new offset mode
+    pub fn with_parity(mut self, parity: OffsetParity) -> HexRectangleStorage<T> {
+        self.parity = parity;
+        self
+    }
+
+    /// Which rows or columns are shoved over
+    pub fn parity(&self) -> OffsetParity {
+        self.parity
+    }
+
+    fn from_grid(grid: Grid<T>, mode: OffsetHexMode) -> HexRectangleStorage<T> {
+        let (orientation, parity) = match mode {
+            OffsetHexMode::OddRows => (HexOrientation::Pointy, OffsetParity::Odd),
+            OffsetHexMode::EvenRows => (HexOrientation::Pointy, OffsetParity::Even),
+            OffsetHexMode::OddColumns => (HexOrientation::Flat, OffsetParity::Odd),
+            OffsetHexMode::EvenColumns => (HexOrientation::Flat, OffsetParity::Even),
+        };
         HexRectangleStorage {
-            grid: Grid::init(y_size, x_size, data),
+            grid,
             orientation,
+            parity,
         }
     }
 
-    /// Construct a new [`HexRectangleStorage`] from a vec of vecs of data.
+    /// The offset mode used to index the grid
+    pub fn offset_mode(&self) -> OffsetHexMode {
+        match (self.orientation, self.parity) {
+            (HexOrientation::Pointy, OffsetParity::Odd) => OffsetHexMode::OddRows,
+            (HexOrientation::Pointy, OffsetParity::Even) => OffsetHexMode::EvenRows,
+            (HexOrientation::Flat, OffsetParity::Odd) => OffsetHexMode::OddColumns,
+            (HexOrientation::Flat, OffsetParity::Even) => OffsetHexMode::EvenColumns,
+        }
+    }
+
+    /// Returns the `[col, row]` index into the grid for the given cell if it is inside the storage
+    pub fn verify_access(&self, cell: Cell) -> Option<[usize; 2]> {
+        let [col, row] = Hex::from(cell).to_offset_coordinates(self.offset_mode());
+        if col.is_negative()
+            || row.is_negative()
+            || col as usize >= self.grid.cols()
+            || row as usize >= self.grid.rows()
+        {
+            return None;
+        }
+        Some([col as usize, row as usize])
+    }
+
+    /// Access data inside the grid. Verifies that the location is a valid cell according to hexagonal coordinate system
+    pub fn get(&self, cell: Cell) -> Option<&T> {
+        let access = self.verify_access(cell)?;
+        self.grid.get(access[1], access[0])
+    }
+
+    /// Access data mutably inside the grid. Verifies that the location is a valid cell according to hexagonal coordinate system
+    pub fn get_mut(&mut self, cell: Cell) -> Option<&mut T> {
+        let access = self.verify_access(cell)?;
+        self.grid.get_mut(access[1], access[0])
+    }
+
+    /// Sets the data at the given Cell, returning the previous data. Verifies that the location is a valid cell according
+    /// to hexagonal coordinate system. Returns None and drops the data if the cell is outside the storage
+    pub fn set(&mut self, cell: Cell, data: T) -> Option<T> {
+        let t = self.get_mut(cell)?;
+        Some(std::mem::replace(t, data))
+    }
+
+    /// Iterates over every cell in the storage, row by row in the order of the grid
+    pub fn cells(&self) -> impl Iterator<Item = Cell> {
+        let (cols, rows, mode) = (self.grid.cols(), self.grid.rows(), self.offset_mode());
+        (0..rows).flat_map(move |row| (0..cols).map(move |col| cell_at_index(mode, [col, row])))
+    }
+
+    /// Iterates over every cell in the storage alongside its data, row by row in the order of the grid
+    pub fn iter(&self) -> impl Iterator<Item = (Cell, &T)> {
+        self.cells().zip(self.grid.iter())
+    }
+
+    /// Iterates mutably over every cell in the storage alongside its data, row by row in the order of the grid
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Cell, &mut T)> {
+        let (cols, mode) = (self.grid.cols(), self.offset_mode());
+        self.grid
+            .iter_mut()
+            .enumerate()
+            .map(move |(i, t)| (cell_at_index(mode, [i % cols, i / cols]), t))
+    }
+
+    /// Iterates over every cell inside the rectangle of the grid spanned by the two corner cells, both included, row
+    /// by row. The rectangle is taken in offset coordinates so it lines up with the rows and columns of the map, and is
+    /// clipped to the storage
+    pub fn cells_in_rect(&self, corner: Cell, opposite_corner: Cell) -> impl Iterator<Item = Cell> {
+        let mode = self.offset_mode();
+        let [a_col, a_row] = Hex::from(corner).to_offset_coordinates(mode);
+        let [b_col, b_row] = Hex::from(opposite_corner).to_offset_coordinates(mode);
+        let (max_col, max_row) = (self.grid.cols() as i32 - 1, self.grid.rows() as i32 - 1);
+        let cols = a_col.min(b_col).max(0)..=a_col.max(b_col).min(max_col);
+        let rows = a_row.min(b_row).max(0)..=a_row.max(b_row).min(max_row);
+        rows.flat_map(move |row| {
+            cols.clone()
+                .map(move |col| Cell::from_offset_coordinates([col, row], mode))
+        })
+    }
+
+    /// Iterates over every cell inside the rectangle spanned by the two corner cells alongside its data. See
+    /// [`HexRectangleStorage::cells_in_rect`]
+    pub fn iter_rect(
+        &self,
+        corner: Cell,
+        opposite_corner: Cell,
+    ) -> impl Iterator<Item = (Cell, &T)> {
+        self.cells_in_rect(corner, opposite_corner)
+            .filter_map(|cell| Some((cell, self.get(cell)?)))
+    }
+
+    /// Returns a copy of the rectangle spanned by the two corner cells. The copy keeps the cells of the data, so it can
+    /// be pasted elsewhere with [`GridStorage::blit`] using the offset between the cells. See
+    /// [`HexRectangleStorage::cells_in_rect`]
+    pub fn copy_region(&self, corner: Cell, opposite_corner: Cell) -> SparseStorage<T>
+    where
+        T: Clone,
+    {
+        self.iter_rect(corner, opposite_corner)
+            .map(|(cell, data)| (cell, data.clone()))
+            .collect()
+    }
+}
+
+impl<T: Default> HexRectangleStorage<T> {
+    /// Rotates or reflects the contents of the storage around `pivot`, returning a storage of the same size and offset
+    /// mode holding the moved data. Cells of the new storage that nothing moved into hold the default data.
     ///
-    /// The data is layed out and accessed according to axial coordinates
-    pub fn new_from_vec(data: Vec<Vec<T>>, orientation: HexOrientation) -> HexRectangleStorage<T>
+    /// Also returns every moved cell that falls outside the rectangle alongside its data. See [`transform_into`]
+    pub fn hex_transformed(
+        self,
+        transform: HexTransform,
+        pivot: Cell,
+    ) -> (HexRectangleStorage<T>, Vec<(Cell, T)>) {
+        let dimensions = self.dimensions();
+        let mut target = HexRectangleStorage::new_with_mode(
+            dimensions.x as usize,
+            dimensions.y as usize,
+            self.offset_mode(),
+        );
+        let outside = transform_into(self, transform, pivot, &mut target);
+        (target, outside)
+    }
+}
+
+impl<T> IntoIterator for HexRectangleStorage<T> {
+    type Item = (Cell, T);
+    type IntoIter = std::iter::Zip<std::vec::IntoIter<Cell>, std::vec::IntoIter<T>>;
+
+    /// Iterates over every cell in the storage alongside its data, row by row in the order of the grid
+    fn into_iter(self) -> Self::IntoIter {
+        let cells: Vec<Cell> = self.cells().collect();
+        cells.into_iter().zip(self.grid.into_vec())
+    }
+}
+
+impl<T> GridStorage<T> for HexRectangleStorage<T> {
+    fn get(&self, cell: Cell) -> Option<&T> {
+        self.get(cell)
+    }
+
+    fn get_mut(&mut self, cell: Cell) -> Option<&mut T> {
+        self.get_mut(cell)
+    }
+
+    fn set(&mut self, cell: Cell, data: T) -> Option<T> {
+        self.set(cell, data)
+    }
+
+    fn contains(&self, cell: Cell) -> bool {
+        self.verify_access(cell).is_some()
+    }
+
+    fn iter<'a>(&'a self) -> impl Iterator<Item = (Cell, &'a T)>
     where
-        T: Default + Clone + Copy,
+        T: 'a,
     {
-        let mut given_tile_count = 0u64;
+        self.iter()
+    }
 
-        for tile_data in data.iter() {
-            given_tile_count += tile_data.len() as u64;
+    fn iter_mut<'a>(&'a mut self) -> impl Iterator<Item = (Cell, &'a mut T)>
+    where
+        T: 'a,
+    {
+        self.iter_mut()
+    }
+
+    fn dimensions(&self) -> UVec2 {
+        self.dimensions()
+    }
+}
+
+/// Converts a grid position to the cell it is stored at, assuming [`OffsetHexMode::OddRows`]. Gives the wrong cells
+/// for every other offset mode
+#[deprecated(note = "use HexRectangleStorage::cells")]
+pub fn convert_2d_array_index_to_rectangle_position(position: UVec2) -> Cell {
+    let y_offset = f32::floor(position.y as f32 / 2.0) as i32;
+    Cell::new(position.x as i32 - y_offset, position.y as i32)
+}
+
+/// Rotates or reflects every cell of `source` around `pivot` and writes its data into `target` at the new position.
+///
+/// Returns every moved cell that falls outside the target alongside its data instead of dropping it. The target should
+/// be a storage with a fixed shape such as a [`HexRectangleStorage`]; use [`SparseStorage::hex_transformed`] to
+/// transform a set of scattered cells
+///
+/// [`SparseStorage::hex_transformed`]: super::sparse::SparseStorage::hex_transformed
+pub fn transform_into<T>(
+    source: impl IntoIterator<Item = (Cell, T)>,
+    transform: HexTransform,
+    pivot: Cell,
+    target: &mut impl GridStorage<T>,
+) -> Vec<(Cell, T)> {
+    let mut outside = Vec::new();
+    for (cell, data) in source {
+        let moved = transform.apply(cell, pivot);
+        match target.get_mut(moved) {
+            Some(t) => *t = data,
+            None => outside.push((moved, data)),
         }
+    }
+    outside
+}
 
-        assert_eq!((data[0].len() * data.len()) as u64, given_tile_count);
+/// The offset mode used by storages built from an orientation alone
+fn default_mode(orientation: HexOrientation) -> OffsetHexMode {
+    match orientation {
+        HexOrientation::Pointy => OffsetHexMode::OddRows,
+        HexOrientation::Flat => OffsetHexMode::OddColumns,
+    }
+}
 
-        let mut grid: Grid<T> = Grid::init(data[0].len(), data.len(), T::default());
-        let mut current_x = 0usize;
-        let mut current_y = 0usize;
-        let row_length = data[0].len();
-        grid.fill_with(|| {
-            let tile = data[current_y][current_x];
-            current_x += 1;
-            if current_x == row_length {
-                current_x = 0;
-                current_y += 1;
-            }
-            tile
-        });
-        HexRectangleStorage { grid, orientation }
+/// Returns the cell stored at the given `[col, row]` index of the grid. The inverse of
+/// [`HexRectangleStorage::verify_access`]
+pub(super) fn cell_at_index(mode: OffsetHexMode, [col, row]: [usize; 2]) -> Cell {
+    Cell::from_offset_coordinates([col as i32, row as i32], mode)
+}
+
+/// Storage structure for a hexagon map shaped like a large hexagon of the given radius around a center cell.
+///
+/// Uses Axial Coordinate System. Each row of the hexagon is stored one after another without any unused space, so
+/// the storage holds exactly `3 * radius * (radius + 1) + 1` cells
+///
+/// See [RedBlobGames Hexagon Map Storage](https://www.redblobgames.com/grids/hexagons/#map-storage)
+#[derive(Hash, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bevy_reflect", derive(Reflect))]
+pub struct HexHexagonStorage<T> {
+    data: Vec<T>,
+    radius: u32,
+    center: Cell,
+}
+
+impl<T> HexHexagonStorage<T> {
+    /// Construct a new [`HexHexagonStorage`] from the default for the given data
+    pub fn new(radius: u32) -> HexHexagonStorage<T>
+    where
+        T: Default,
+    {
+        Self::new_from_fn(radius, |_| T::default())
+    }
+
+    /// Construct a new [`HexHexagonStorage`] by cloning the given data
+    pub fn new_uniform(radius: u32, data: T) -> HexHexagonStorage<T>
+    where
+        T: Clone,
+    {
+        HexHexagonStorage {
+            data: vec![data; hexagon_len(radius)],
+            radius,
+            center: Cell::ORIGIN,
+        }
+    }
+
+    /// Construct a new [`HexHexagonStorage`] by calling `f` with every cell of the hexagon, relative to a center of
+    /// [`Cell::ORIGIN`]
+    pub fn new_from_fn(radius: u32, f: impl FnMut(Cell) -> T) -> HexHexagonStorage<T> {
+        HexHexagonStorage {
+            data: hexagon_cells(radius, Cell::ORIGIN).map(f).collect(),
+            radius,
+            center: Cell::ORIGIN,
+        }
+    }
+
+    /// Moves the center of the hexagon to the given cell
+    pub fn with_center(mut self, center: Cell) -> HexHexagonStorage<T> {
+        self.center = center;
+        self
+    }
+
+    pub fn radius(&self) -> u32 {
+        self.radius
+    }
+
+    pub fn center(&self) -> Cell {
+        self.center
+    }
+
+    /// The number of cells in the storage
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// The width and height of the hexagon in cells
+    pub fn dimensions(&self) -> UVec2 {
+        UVec2::splat(2 * self.radius + 1)
+    }
+
+    /// Returns the index into the data for the given cell if it is inside the hexagon
+    pub fn verify_access(&self, cell: Cell) -> Option<usize> {
+        let local = Cell::new(
+            cell.x.checked_sub(self.center.x)?,
+            cell.y.checked_sub(self.center.y)?,
+        );
+        let n = self.radius as i64;
+        let (q, r) = (local.x as i64, local.y as i64);
+        if q.abs() > n || r.abs() > n || (q + r).abs() > n {
+            return None;
+        }
+
+        // Rows grow by one cell until the middle row and shrink by one after it
+        let k = r + n;
+        let row_start = if k <= n {
+            k * (n + 1) + k * (k - 1) / 2
+        } else {
+            let past_middle = k - n;
+            n * (n + 1) + n * (n - 1) / 2 + past_middle * (3 * n + 1)
+                - past_middle * (n + k - 1) / 2
+        };
+        let row_min = (-n).max(-r - n);
+        Some((row_start + q - row_min) as usize)
+    }
+
+    /// Access data inside the hexagon. Returns None if the cell is outside the storage
+    pub fn get(&self, cell: Cell) -> Option<&T> {
+        let index = self.verify_access(cell)?;
+        self.data.get(index)
+    }
+
+    /// Access data mutably inside the hexagon. Returns None if the cell is outside the storage
+    pub fn get_mut(&mut self, cell: Cell) -> Option<&mut T> {
+        let index = self.verify_access(cell)?;
+        self.data.get_mut(index)
+    }
+
+    /// Sets the data at the given Cell, returning the previous data. Returns None and drops the data if the cell is
+    /// outside the storage
+    pub fn set(&mut self, cell: Cell, data: T) -> Option<T> {
+        let t = self.get_mut(cell)?;
+        Some(std::mem::replace(t, data))
+    }
+
+    /// Iterates over every cell of the hexagon, row by row
+    pub fn cells(&self) -> impl Iterator<Item = Cell> {
+        hexagon_cells(self.radius, self.center)
+    }
+
+    /// Iterates over every cell of the hexagon alongside its data, row by row
+    pub fn iter(&self) -> impl Iterator<Item = (Cell, &T)> {
+        self.cells().zip(self.data.iter())
+    }
+
+    /// Iterates mutably over every cell of the hexagon alongside its data, row by row
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Cell, &mut T)> {
+        hexagon_cells(self.radius, self.center).zip(self.data.iter_mut())
+    }
+}
+
+impl<T> GridStorage<T> for HexHexagonStorage<T> {
+    fn get(&self, cell: Cell) -> Option<&T> {
+        self.get(cell)
+    }
+
+    fn get_mut(&mut self, cell: Cell) -> Option<&mut T> {
+        self.get_mut(cell)
+    }
+
+    fn set(&mut self, cell: Cell, data: T) -> Option<T> {
+        self.set(cell, data)
+    }
+
+    fn contains(&self, cell: Cell) -> bool {
+        self.verify_access(cell).is_some()
+    }
+
+    fn iter<'a>(&'a self) -> impl Iterator<Item = (Cell, &'a T)>
+    where
+        T: 'a,
+    {
+        self.iter()
+    }
+
+    fn iter_mut<'a>(&'a mut self) -> impl Iterator<Item = (Cell, &'a mut T)>
+    where
+        T: 'a,
+    {
+        self.iter_mut()
+    }
+
+    fn dimensions(&self) -> UVec2 {
+        self.dimensions()
+    }
+}
+
+/// The number of cells in a hexagon of the given radius
+fn hexagon_len(radius: u32) -> usize {
+    let radius = radius as usize;
+    3 * radius * (radius + 1) + 1
+}
+
+/// Every cell of a hexagon in storage order, row by row with `x` increasing along each row
+fn hexagon_cells(radius: u32, center: Cell) -> impl Iterator<Item = Cell> {
+    let n = radius as i32;
+    (-n..=n).flat_map(move |r| {
+        ((-n).max(-r - n)..=n.min(-r + n)).map(move |q| center + Cell::new(q, r))
+    })
+}
+
+/// Storage structure for a hexagon map shaped like a parallelogram.
+///
+/// Uses Axial Coordinate System. Holds every cell with `0 <= x < x_size` and `0 <= y < y_size`, which lines up
+/// exactly with the rows and columns of the grid
+///
+/// See [RedBlobGames Hexagon Map Storage](https://www.redblobgames.com/grids/hexagons/#map-storage)
+#[derive(Hash, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bevy_reflect", derive(Reflect))]
+pub struct HexParallelogramStorage<T> {
+    pub grid: Grid<T>,
+}
+
+impl<T> HexParallelogramStorage<T> {
+    /// Construct a new [`HexParallelogramStorage`] from the default for the given data
+    pub fn new(x_size: usize, y_size: usize) -> HexParallelogramStorage<T>
+    where
+        T: Default,
+    {
+        HexParallelogramStorage {
+            grid: Grid::new(y_size, x_size),
+        }
+    }
+
+    /// Construct a new [`HexParallelogramStorage`] by cloning the given data
+    pub fn new_uniform(x_size: usize, y_size: usize, data: T) -> HexParallelogramStorage<T>
+    where
+        T: Clone,
+    {
+        HexParallelogramStorage {
+            grid: Grid::init(y_size, x_size, data),
+        }
     }
 
+    /// Construct a new [`HexParallelogramStorage`] from a vec of vecs of data.
+    ///
+    /// Each inner vec is a row of the map, so `data[y][x]` is stored at `Cell::new(x, y)`
+    ///
+    /// # Panics
+    /// If the rows have different lengths. See [`HexParallelogramStorage::try_from_vec`]
+    pub fn new_from_vec(data: Vec<Vec<T>>) -> HexParallelogramStorage<T> {
+        let row_length = row_length(&data).unwrap_or_else(|error| panic!("{error}"));
+
+        HexParallelogramStorage {
+            grid: Grid::from_vec(data.into_iter().flatten().collect(), row_length),
+        }
+    }
+
+    /// Construct a new [`HexParallelogramStorage`] from a vec of vecs of data like
+    /// [`HexParallelogramStorage::new_from_vec`]. Returns [`LettucesError::Empty`] if there is no data or
+    /// [`LettucesError::Ragged`] if the rows have different lengths
+    pub fn try_from_vec(data: Vec<Vec<T>>) -> Result<HexParallelogramStorage<T>, LettucesError> {
+        if row_length(&data)? == 0 {
+            return Err(LettucesError::Empty);
+        }
+        Ok(HexParallelogramStorage::new_from_vec(data))
+    }
+
+    pub fn dimensions(&self) -> UVec2 {
+        UVec2 {
+            x: self.grid.cols() as u32,
+            y: self.grid.rows() as u32,
+        }
+    }
+
+    /// Returns the `[col, row]` index into the grid for the given cell if it is inside the storage
     pub fn verify_access(&self, cell: Cell) -> Option<[usize; 2]> {
-        match self.orientation {
-            HexOrientation::Pointy => {
-                let col = cell.x + f32::floor(cell.y as f32 / 2.0) as i32;
-                if cell.y.is_negative()
-                    || col.is_negative()
-                    || col as usize > self.grid.cols()
-                    || cell.y as usize > self.grid.rows()
-                {
-                    return None;
-                }
-                Some([col as usize, cell.y as usize])
-            }
-            HexOrientation::Flat => {
-                let col = cell.y + f32::floor(cell.x as f32 / 2.0) as i32;
-                if cell.x.is_negative()
-                    || col.is_negative()
-                    || col as usize > self.grid.cols()
-                    || cell.x as usize > self.grid.rows()
-                {
-                    return None;
-                }
-                Some([cell.x as usize, col as usize])
-            }
+        if cell.x.is_negative()
+            || cell.y.is_negative()
+            || cell.x as usize >= self.grid.cols()
+            || cell.y as usize >= self.grid.rows()
+        {
+            return None;
         }
+        Some([cell.x as usize, cell.y as usize])
     }
 
-    /// Access data inside the grid. Verifies that the location is a valid cell according to hexagonal coordinate system
+    /// Access data inside the grid. Returns None if the cell is outside the storage
     pub fn get(&self, cell: Cell) -> Option<&T> {
         let access = self.verify_access(cell)?;
         self.grid.get(access[1], access[0])
     }
 
-    /// Access data mutably inside the grid. Verifies that the location is a valid cell according to hexagonal coordinate system
+    /// Access data mutably inside the grid. Returns None if the cell is outside the storage
     pub fn get_mut(&mut self, cell: Cell) -> Option<&mut T> {
         let access = self.verify_access(cell)?;
         self.grid.get_mut(access[1], access[0])
     }
 
-    /// Sets the data at the given Cell. Verifies that the location is a valid cell according to hexagonal coordinate system
-    pub fn set(&mut self, cell: Cell, data: T) {
-        let Some(access) = self.verify_access(cell) else {
-            return;
-        };
-        let Some(t) = self.grid.get_mut(access[1], access[0]) else {
-            return;
-        };
-        *t = data;
+    /// Sets the data at the given Cell, returning the previous data. Returns None and drops the data if the cell is
+    /// outside the storage
+    pub fn set(&mut self, cell: Cell, data: T) -> Option<T> {
+        let t = self.get_mut(cell)?;
+        Some(std::mem::replace(t, data))
+    }
+
+    /// Iterates over every cell of the parallelogram, row by row
+    pub fn shape(&self) -> impl Iterator<Item = Cell> {
+        let (cols, rows) = (self.grid.cols() as i32, self.grid.rows() as i32);
+        (0..rows).flat_map(move |y| (0..cols).map(move |x| Cell::new(x, y)))
+    }
+}
+
+impl<T> GridStorage<T> for HexParallelogramStorage<T> {
+    fn get(&self, cell: Cell) -> Option<&T> {
+        self.get(cell)
+    }
+
+    fn get_mut(&mut self, cell: Cell) -> Option<&mut T> {
+        self.get_mut(cell)
+    }
+
+    fn set(&mut self, cell: Cell, data: T) -> Option<T> {
+        self.set(cell, data)
+    }
+
+    fn contains(&self, cell: Cell) -> bool {
+        self.verify_access(cell).is_some()
+    }
+
+    fn iter<'a>(&'a self) -> impl Iterator<Item = (Cell, &'a T)>
+    where
+        T: 'a,
+    {
+        self.shape().zip(self.grid.iter())
+    }
+
+    fn iter_mut<'a>(&'a mut self) -> impl Iterator<Item = (Cell, &'a mut T)>
+    where
+        T: 'a,
+    {
+        let (cols, rows) = (self.grid.cols() as i32, self.grid.rows() as i32);
+        (0..rows)
+            .flat_map(move |y| (0..cols).map(move |x| Cell::new(x, y)))
+            .zip(self.grid.iter_mut())
+    }
+
+    fn dimensions(&self) -> UVec2 {
+        self.dimensions()
+    }
+}
+
+/// Storage structure for a hexagon map shaped like a triangle.
+///
+/// Uses Axial Coordinate System. Holds every cell with `x >= 0`, `y >= 0` and `x + y < size`. Each row is one cell
+/// shorter than the one before it and rows are stored one after another without any unused space
+///
+/// See [RedBlobGames Hexagon Map Storage](https://www.redblobgames.com/grids/hexagons/#map-storage)
+#[derive(Hash, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bevy_reflect", derive(Reflect))]
+pub struct HexTriangleStorage<T> {
+    data: Vec<T>,
+    size: usize,
+}
+
+impl<T> HexTriangleStorage<T> {
+    /// Construct a new [`HexTriangleStorage`] from the default for the given data
+    pub fn new(size: usize) -> HexTriangleStorage<T>
+    where
+        T: Default,
+    {
+        HexTriangleStorage {
+            data: (0..triangle_len(size)).map(|_| T::default()).collect(),
+            size,
+        }
+    }
+
+    /// Construct a new [`HexTriangleStorage`] by cloning the given data
+    pub fn new_uniform(size: usize, data: T) -> HexTriangleStorage<T>
+    where
+        T: Clone,
+    {
+        HexTriangleStorage {
+            data: vec![data; triangle_len(size)],
+            size,
+        }
+    }
+
+    /// Construct a new [`HexTriangleStorage`] from a vec of vecs of data.
+    ///
+    /// Each inner vec is a row of the map, so `data[y][x]` is stored at `Cell::new(x, y)`. The first row sets the size
+    /// of the triangle and every following row must be one element shorter
+    ///
+    /// # Panics
+    /// If the rows do not form a triangle. See [`HexTriangleStorage::try_from_vec`]
+    pub fn new_from_vec(data: Vec<Vec<T>>) -> HexTriangleStorage<T> {
+        let size = triangle_size(&data).unwrap_or_else(|error| panic!("{error}"));
+
+        HexTriangleStorage {
+            data: data.into_iter().flatten().collect(),
+            size,
+        }
+    }
+
+    /// Construct a new [`HexTriangleStorage`] from a vec of vecs of data like [`HexTriangleStorage::new_from_vec`].
+    /// Returns [`LettucesError::Empty`] if there is no data, [`LettucesError::SizeMismatch`] if the number of rows
+    /// does not match the length of the first row, or [`LettucesError::Ragged`] if a row is not one element shorter
+    /// than the row before it
+    pub fn try_from_vec(data: Vec<Vec<T>>) -> Result<HexTriangleStorage<T>, LettucesError> {
+        if triangle_size(&data)? == 0 {
+            return Err(LettucesError::Empty);
+        }
+        Ok(HexTriangleStorage::new_from_vec(data))
+    }
+
+    /// The number of cells along each side of the triangle
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// The number of cells in the storage
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    pub fn dimensions(&self) -> UVec2 {
+        UVec2::splat(self.size as u32)
+    }
+
+    /// Returns the index into the data for the given cell if it is inside the triangle
+    pub fn verify_access(&self, cell: Cell) -> Option<usize> {
+        if cell.x.is_negative() || cell.y.is_negative() {
+            return None;
+        }
+        // Neither is negative, so adding them as usize cannot overflow
+        let (x, y) = (cell.x as usize, cell.y as usize);
+        if x + y >= self.size {
+            return None;
+        }
+        Some(y * (2 * self.size + 1 - y) / 2 + x)
+    }
+
+    /// Access data inside the triangle. Returns None if the cell is outside the storage
+    pub fn get(&self, cell: Cell) -> Option<&T> {
+        let index = self.verify_access(cell)?;
+        self.data.get(index)
+    }
+
+    /// Access data mutably inside the triangle. Returns None if the cell is outside the storage
+    pub fn get_mut(&mut self, cell: Cell) -> Option<&mut T> {
+        let index = self.verify_access(cell)?;
+        self.data.get_mut(index)
+    }
+
+    /// Sets the data at the given Cell, returning the previous data. Returns None and drops the data if the cell is
+    /// outside the storage
+    pub fn set(&mut self, cell: Cell, data: T) -> Option<T> {
+        let t = self.get_mut(cell)?;
+        Some(std::mem::replace(t, data))
+    }
+
+    /// Iterates over every cell of the triangle, row by row
+    pub fn shape(&self) -> impl Iterator<Item = Cell> {
+        let size = self.size as i32;
+        (0..size).flat_map(move |y| (0..size - y).map(move |x| Cell::new(x, y)))
+    }
+}
+
+impl<T> GridStorage<T> for HexTriangleStorage<T> {
+    fn get(&self, cell: Cell) -> Option<&T> {
+        self.get(cell)
+    }
+
+    fn get_mut(&mut self, cell: Cell) -> Option<&mut T> {
+        self.get_mut(cell)
+    }
+
+    fn set(&mut self, cell: Cell, data: T) -> Option<T> {
+        self.set(cell, data)
+    }
+
+    fn contains(&self, cell: Cell) -> bool {
+        self.verify_access(cell).is_some()
+    }
+
+    fn iter<'a>(&'a self) -> impl Iterator<Item = (Cell, &'a T)>
+    where
+        T: 'a,
+    {
+        self.shape().zip(self.data.iter())
+    }
+
+    fn iter_mut<'a>(&'a mut self) -> impl Iterator<Item = (Cell, &'a mut T)>
+    where
+        T: 'a,
+    {
+        let size = self.size as i32;
+        (0..size)
+            .flat_map(move |y| (0..size - y).map(move |x| Cell::new(x, y)))
+            .zip(self.data.iter_mut())
+    }
+
+    fn dimensions(&self) -> UVec2 {
+        self.dimensions()
+    }
+}
+
+/// The number of cells in a triangle with sides of the given size
+fn triangle_len(size: usize) -> usize {
+    size * (size + 1) / 2
+}
+
+/// Returns the size of the triangle formed by the rows of data, or an error if they do not form a triangle
+fn triangle_size<T>(data: &[Vec<T>]) -> Result<usize, LettucesError> {
+    let size = data.first().map_or(0, |row| row.len());
+    if data.len() != size {
+        return Err(LettucesError::SizeMismatch {
+            expected: size,
+            found: data.len(),
+        });
+    }
+    match data
+        .iter()
+        .enumerate()
+        .find(|(y, row)| row.len() != size - y)
+    {
+        Some((row, data)) => Err(LettucesError::Ragged {
+            row,
+            expected: size - row,
+            found: data.len(),
+        }),
+        None => Ok(size),
     }
 }
 
 #[cfg(test)]
 mod tests {
     use glam::UVec2;
-    use hexx::HexOrientation;
+    use hexx::{HexOrientation, OffsetHexMode};
+
+    use crate::{cell::Cell, error::LettucesError};
 
-    use crate::cell::Cell;
+    use crate::storage::GridStorage;
 
-    use super::HexRectangleStorage;
+    use super::{
+        HexHexagonStorage, HexParallelogramStorage, HexRectangleStorage, HexTriangleStorage,
+        OffsetParity,
+    };
 
     #[derive(Default, Clone, Copy, PartialEq, Eq, Debug)]
     struct TileData {
@@ -196,6 +952,29 @@ mod tests {
         assert!(data.is_none());
     }
 
+    #[test]
+    fn test_access_bounds() {
+        let pointy = HexRectangleStorage::<TileData>::new(4, 3, HexOrientation::Pointy);
+        assert_eq!(pointy.verify_access(Cell::new(3, 0)), Some([3, 0]));
+        assert_eq!(pointy.verify_access(Cell::new(4, 0)), None);
+        assert_eq!(pointy.verify_access(Cell::new(-1, 2)), Some([0, 2]));
+        assert_eq!(pointy.verify_access(Cell::new(-1, 3)), None);
+
+        // Wider than tall, so the column and row limits can not be swapped
+        let mut flat = HexRectangleStorage::<TileData>::new(5, 2, HexOrientation::Flat);
+        assert_eq!(flat.verify_access(Cell::new(4, -2)), Some([4, 0]));
+        assert_eq!(flat.verify_access(Cell::new(4, -1)), Some([4, 1]));
+        assert_eq!(flat.verify_access(Cell::new(4, 0)), None);
+        assert_eq!(flat.verify_access(Cell::new(5, -2)), None);
+
+        let data = TileData {
+            position: Cell::new(4, -2),
+        };
+        flat.set(Cell::new(4, -2), data);
+        assert_eq!(flat.get(Cell::new(4, -2)), Some(&data));
+        assert_eq!(flat.grid.get(0, 4), Some(&data));
+    }
+
     #[test]
     fn test_correct_access_flat() {
         let map = make_map(UVec2::new(15, 15), HexOrientation::Flat);
@@ -323,16 +1102,290 @@ mod tests {
             let mut row = vec![];
             for x in 0..size.x {
                 row.push(TileData {
-                    position: Cell::from(Cell::from_offset_coordinates([x as i32, y as i32], mode)),
+                    position: Cell::from_offset_coordinates([x as i32, y as i32], mode),
                 });
             }
             map.push(row);
         }
         map
     }
-}
 
-pub fn convert_2d_array_index_to_rectangle_position(position: UVec2) -> Cell {
-    let y_offset = f32::floor(position.y as f32 / 2.0) as i32;
-    Cell::new(position.x as i32 - y_offset, position.y as i32)
+    #[test]
+    fn test_hexagon_access() {
+        for radius in [0, 1, 4, 9] {
+            let map = HexHexagonStorage::new_from_fn(radius, |cell| cell);
+            assert_eq!(map.len(), 3 * radius as usize * (radius as usize + 1) + 1);
+
+            for (i, (cell, data)) in map.iter().enumerate() {
+                assert_eq!(cell, *data);
+                assert_eq!(map.verify_access(cell), Some(i));
+            }
+        }
+
+        let mut map = HexHexagonStorage::new_uniform(3, 0).with_center(Cell::new(-5, 2));
+        assert_eq!(map.dimensions(), UVec2::new(7, 7));
+        assert!(map.get(Cell::new(-5, 2)).is_some());
+        assert!(map.get(Cell::new(-2, -1)).is_some());
+        assert!(map.get(Cell::new(-8, 5)).is_some());
+        assert!(map.get(Cell::new(-2, 3)).is_none());
+        assert!(map.get(Cell::new(-9, 2)).is_none());
+        assert!(map.get(Cell::new(-5, 6)).is_none());
+
+        assert_eq!(map.set(Cell::new(-4, 2), 3), Some(0));
+        assert_eq!(map.set(Cell::new(0, 0), 3), None);
+        assert_eq!(map.iter().filter(|(_, t)| **t == 3).count(), 1);
+
+        let far = HexHexagonStorage::new_uniform(2, 0).with_center(Cell::new(i32::MIN + 2, 0));
+        assert!(far.get(Cell::new(i32::MAX, 0)).is_none());
+        assert!(far.get(Cell::new(i32::MIN, 0)).is_some());
+    }
+
+    #[test]
+    fn test_parallelogram_access() {
+        let map = HexParallelogramStorage::new_from_vec(
+            (0..3)
+                .map(|y| (0..5).map(|x| Cell::new(x, y)).collect())
+                .collect(),
+        );
+
+        assert_eq!(map.dimensions(), UVec2::new(5, 3));
+        assert_eq!(map.shape().count(), 15);
+        for (cell, data) in GridStorage::iter(&map) {
+            assert_eq!(cell, *data);
+            assert_eq!(map.get(cell), Some(&cell));
+        }
+        assert!(map.get(Cell::new(5, 0)).is_none());
+        assert!(map.get(Cell::new(0, 3)).is_none());
+        assert!(map.get(Cell::new(-1, 1)).is_none());
+    }
+
+    #[test]
+    fn test_triangle_access() {
+        for size in [0, 1, 2, 7] {
+            let map = HexTriangleStorage::new_from_vec(
+                (0..size)
+                    .map(|y| (0..size - y).map(|x| Cell::new(x, y)).collect())
+                    .collect(),
+            );
+            assert_eq!(map.len(), (size * (size + 1) / 2) as usize);
+
+            for (i, (cell, data)) in GridStorage::iter(&map).enumerate() {
+                assert_eq!(cell, *data);
+                assert_eq!(map.verify_access(cell), Some(i));
+            }
+        }
+
+        let mut map = HexTriangleStorage::new_uniform(4, 0);
+        assert!(map.get(Cell::new(3, 0)).is_some());
+        assert!(map.get(Cell::new(0, 3)).is_some());
+        assert!(map.get(Cell::new(2, 2)).is_none());
+        assert!(map.get(Cell::new(-1, 2)).is_none());
+        assert!(map.get(Cell::new(i32::MAX, i32::MAX)).is_none());
+        assert_eq!(map.set(Cell::new(1, 2), 4), Some(0));
+        assert_eq!(map.set(Cell::new(4, 0), 4), None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_triangle_wrong_rows() {
+        HexTriangleStorage::new_from_vec(vec![vec![1, 2, 3], vec![4, 5, 6], vec![7]]);
+    }
+
+    #[test]
+    fn test_offset_modes() {
+        for mode in [
+            OffsetHexMode::OddRows,
+            OffsetHexMode::EvenRows,
+            OffsetHexMode::OddColumns,
+            OffsetHexMode::EvenColumns,
+        ] {
+            let data = (0..3)
+                .map(|row| {
+                    (0..5)
+                        .map(|col| Cell::from_offset_coordinates([col, row], mode))
+                        .collect()
+                })
+                .collect();
+            let map = HexRectangleStorage::from_vec_with_mode(data, mode);
+
+            assert_eq!(map.offset_mode(), mode);
+            assert_eq!(map.dimensions(), UVec2::new(5, 3));
+            for (cell, data) in GridStorage::iter(&map) {
+                assert_eq!(cell, *data);
+                assert_eq!(map.get(cell), Some(&cell));
+            }
+        }
+
+        let map = HexRectangleStorage::new_uniform(4, 4, 0, HexOrientation::Pointy)
+            .with_parity(OffsetParity::Even);
+        assert_eq!(map.orientation, HexOrientation::Pointy);
+        assert_eq!(map.offset_mode(), OffsetHexMode::EvenRows);
+        assert!(map.get(Cell::new(-1, 1)).is_some());
+        assert!(map.get(Cell::new(3, 1)).is_none());
+    }
+
+    #[test]
+    fn test_rectangle_iteration() {
+        for orientation in [HexOrientation::Pointy, HexOrientation::Flat] {
+            let mut map = HexRectangleStorage::new(5, 4, orientation);
+            for (cell, data) in map.iter_mut() {
+                *data = cell;
+            }
+
+            assert_eq!(map.cells().count(), 20);
+            assert!(map.iter().all(|(cell, data)| cell == *data));
+
+            let corner = map.cells().nth(6).unwrap();
+            let opposite = map.cells().nth(13).unwrap();
+            let rect: Vec<Cell> = map.iter_rect(opposite, corner).map(|(_, t)| *t).collect();
+            assert_eq!(
+                rect,
+                [6, 7, 8, 11, 12, 13].map(|i| map.cells().nth(i).unwrap())
+            );
+            assert_eq!(
+                map.cells_in_rect(Cell::new(-20, -20), Cell::new(20, 20))
+                    .count(),
+                20
+            );
+
+            let owned: Vec<(Cell, Cell)> = map.clone().into_iter().collect();
+            assert_eq!(owned.len(), 20);
+            assert!(owned.iter().all(|(cell, data)| cell == data));
+        }
+    }
+
+    #[test]
+    fn test_try_from_vec() {
+        assert!(HexRectangleStorage::try_from_vec(
+            vec![vec![1, 2], vec![3, 4]],
+            HexOrientation::Flat
+        )
+        .is_ok());
+        assert_eq!(
+            HexParallelogramStorage::try_from_vec(vec![vec![1, 2], vec![3]]).err(),
+            Some(LettucesError::Ragged {
+                row: 1,
+                expected: 2,
+                found: 1
+            })
+        );
+        assert_eq!(
+            HexTriangleStorage::try_from_vec(vec![vec![1, 2, 3], vec![4, 5, 6], vec![7]]).err(),
+            Some(LettucesError::Ragged {
+                row: 1,
+                expected: 2,
+                found: 3
+            })
+        );
+        assert_eq!(
+            HexTriangleStorage::try_from_vec(vec![vec![1, 2, 3], vec![4, 5]]).err(),
+            Some(LettucesError::SizeMismatch {
+                expected: 3,
+                found: 2
+            })
+        );
+        assert_eq!(
+            HexTriangleStorage::<u32>::try_from_vec(vec![]).err(),
+            Some(LettucesError::Empty)
+        );
+
+        let mut map = HexRectangleStorage::new_uniform(3, 3, 0, HexOrientation::Pointy);
+        assert_eq!(
+            map.try_set(Cell::new(5, 5), 1),
+            Err(LettucesError::OutOfBounds(Cell::new(5, 5)))
+        );
+    }
+
+    #[test]
+    fn test_hex_transform() {
+        use crate::cell::hex::HexTransform;
+
+        let pivot = Cell::new(2, 1);
+        let cell = Cell::new(4, -1);
+        for transform in [
+            HexTransform::RotateCw(1),
+            HexTransform::RotateCcw(2),
+            HexTransform::ReflectX,
+            HexTransform::ReflectY,
+            HexTransform::ReflectZ,
+        ] {
+            let moved = cell.hex_transform(transform, pivot);
+            assert_eq!(
+                hexx::Hex::from(moved).unsigned_distance_to(pivot.into()),
+                hexx::Hex::from(cell).unsigned_distance_to(pivot.into())
+            );
+            assert_eq!(transform.inverse().apply(moved, pivot), cell);
+        }
+        assert_eq!(cell.hex_transform(HexTransform::RotateCw(6), pivot), cell);
+        assert_eq!(pivot.hex_transform(HexTransform::ReflectY, pivot), pivot);
+    }
+
+    #[test]
+    fn test_rectangle_transform() {
+        use crate::cell::hex::HexTransform;
+
+        let mut map = HexRectangleStorage::new(7, 7, HexOrientation::Pointy);
+        for (cell, data) in map.iter_mut() {
+            *data = Some(cell);
+        }
+        let pivot = map.cells().nth(24).unwrap();
+
+        let (rotated, outside) = map
+            .clone()
+            .hex_transformed(HexTransform::RotateCw(1), pivot);
+        assert!(!outside.is_empty());
+        assert_eq!(
+            rotated.iter().filter(|(_, t)| t.is_some()).count() + outside.len(),
+            49
+        );
+        for (cell, data) in rotated.iter() {
+            if let Some(original) = data {
+                assert_eq!(
+                    original.hex_transform(HexTransform::RotateCw(1), pivot),
+                    cell
+                );
+            }
+        }
+        for (cell, data) in &outside {
+            assert!(rotated.get(*cell).is_none());
+            assert_eq!(
+                data.unwrap()
+                    .hex_transform(HexTransform::RotateCw(1), pivot),
+                *cell
+            );
+        }
+
+        let (back, _) = rotated.hex_transformed(HexTransform::RotateCcw(1), pivot);
+        assert_eq!(back.get(pivot), Some(&Some(pivot)));
+    }
+
+    #[test]
+    fn test_copy_and_blit() {
+        let mut map = HexRectangleStorage::new(6, 6, HexOrientation::Pointy);
+        for (cell, data) in map.iter_mut() {
+            *data = Some(cell);
+        }
+        let corner = Cell::from_offset_coordinates([1, 1], OffsetHexMode::OddRows);
+        let opposite = Cell::from_offset_coordinates([2, 2], OffsetHexMode::OddRows);
+        let prefab = map.copy_region(corner, opposite);
+        assert_eq!(prefab.len(), 4);
+        assert_eq!(map.copy_region(corner, Cell::new(100, 100)).len(), 25);
+
+        let mut target = HexRectangleStorage::<Option<Cell>>::new(6, 6, HexOrientation::Pointy);
+        let offset = Cell::new(4, 0);
+        target.blit(&prefab, offset);
+        for (cell, data) in prefab.iter() {
+            if target.contains(cell + offset) {
+                assert_eq!(target.get(cell + offset), Some(data));
+            }
+        }
+        assert_eq!(target.iter().filter(|(_, t)| t.is_some()).count(), 2);
+
+        let mut masked = HexRectangleStorage::new_uniform(6, 6, 0, HexOrientation::Pointy);
+        let mut stamp = HexRectangleStorage::new(2, 2, HexOrientation::Pointy);
+        stamp.set(Cell::ZERO, Some(5));
+        masked.blit_masked(&stamp, corner);
+        assert_eq!(masked.get(corner), Some(&5));
+        assert_eq!(masked.iter().filter(|(_, t)| **t != 0).count(), 1);
+    }
 }
@@ -1,5 +1,148 @@
+use glam::UVec2;
+
+use crate::{cell::Cell, error::LettucesError};
+
+pub mod chunked;
 pub mod grid;
 #[cfg(feature = "hex")]
 pub mod hex;
+pub mod sparse;
 #[cfg(feature = "square")]
 pub mod square;
+pub mod view;
+
+/// Cell keyed access shared by every storage in this module.
+///
+/// Allows writing logic such as pathfinding or saving and loading once, generic over the shape of the map.
+pub trait GridStorage<T> {
+    /// Access the data at the given cell. Returns None if the cell is outside the storage
+    fn get(&self, cell: Cell) -> Option<&T>;
+
+    /// Access the data at the given cell mutably. Returns None if the cell is outside the storage
+    fn get_mut(&mut self, cell: Cell) -> Option<&mut T>;
+
+    /// Sets the data at the given cell, returning the previous data. Returns None and drops the data if the cell is
+    /// outside the storage
+    fn set(&mut self, cell: Cell, data: T) -> Option<T>;
+
+    /// Returns true if the given cell is inside the storage
+    fn contains(&self, cell: Cell) -> bool;
+
+    /// Iterate over every cell in the storage alongside its data
+    fn iter<'a>(&'a self) -> impl Iterator<Item = (Cell, &'a T)>
+    where
+        T: 'a;
+
+    /// Iterate mutably over every cell in the storage alongside its data
+    fn iter_mut<'a>(&'a mut self) -> impl Iterator<Item = (Cell, &'a mut T)>
+    where
+        T: 'a;
+
+    /// The size of the underlying storage in columns (x) and rows (y)
+    fn dimensions(&self) -> UVec2;
+
+    /// Access the data at the given cell. Returns [`LettucesError::OutOfBounds`] if the cell is outside the storage
+    fn try_get(&self, cell: Cell) -> Result<&T, LettucesError> {
+        self.get(cell).ok_or(LettucesError::OutOfBounds(cell))
+    }
+
+    /// Access the data at the given cell mutably. Returns [`LettucesError::OutOfBounds`] if the cell is outside the
+    /// storage
+    fn try_get_mut(&mut self, cell: Cell) -> Result<&mut T, LettucesError> {
+        self.get_mut(cell).ok_or(LettucesError::OutOfBounds(cell))
+    }
+
+    /// Sets the data at the given cell, returning the previous data if there was any. Returns
+    /// [`LettucesError::OutOfBounds`] if the cell is outside the storage instead of silently dropping the data
+    fn try_set(&mut self, cell: Cell, data: T) -> Result<Option<T>, LettucesError> {
+        if !self.contains(cell) {
+            return Err(LettucesError::OutOfBounds(cell));
+        }
+        Ok(self.set(cell, data))
+    }
+
+    /// Copies every cell of `src` into the storage, moved by `offset`. Cells that land outside the storage are
+    /// skipped, while storages without fixed bounds such as [`sparse::SparseStorage`] take every cell. See
+    /// [`GridStorage::try_set`]
+    fn blit<S: GridStorage<T>>(&mut self, src: &S, offset: Cell)
+    where
+        T: Clone,
+    {
+        for (cell, data) in src.iter() {
+            let _ = self.try_set(cell + offset, data.clone());
+        }
+    }
+
+    /// Copies every cell of `src` into the storage like [`GridStorage::blit`], skipping the cells of `src` that are
+    /// None so the data below them is kept
+    fn blit_masked<S: GridStorage<Option<T>>>(&mut self, src: &S, offset: Cell)
+    where
+        T: Clone,
+    {
+        for (cell, data) in src.iter() {
+            if let Some(data) = data {
+                let _ = self.try_set(cell + offset, data.clone());
+            }
+        }
+    }
+}
+
+#[cfg(all(test, any(feature = "square", feature = "hex")))]
+mod tests {
+    use crate::cell::Cell;
+
+    use super::GridStorage;
+
+    fn fill_with_cells<S: GridStorage<Cell>>(storage: &mut S) {
+        for (cell, data) in storage.iter_mut() {
+            *data = cell;
+        }
+    }
+
+    fn assert_cells_match<S: GridStorage<Cell>>(storage: &S) {
+        let dimensions = storage.dimensions();
+        assert_eq!(
+            storage.iter().count(),
+            (dimensions.x * dimensions.y) as usize
+        );
+        for (cell, data) in storage.iter() {
+            assert!(storage.contains(cell));
+            assert_eq!(storage.get(cell), Some(&cell));
+            assert_eq!(*data, cell);
+        }
+    }
+
+    #[cfg(feature = "square")]
+    #[test]
+    fn test_square_storage_trait() {
+        use super::square::SquareStorage;
+
+        let mut storage = SquareStorage::new(5, 3).with_origin(Cell::new(-1, -1));
+        fill_with_cells(&mut storage);
+        assert_cells_match(&storage);
+        assert!(!storage.contains(Cell::new(4, 0)));
+        assert_eq!(
+            GridStorage::set(&mut storage, Cell::new(3, 1), Cell::ZERO),
+            Some(Cell::new(3, 1))
+        );
+    }
+
+    #[cfg(feature = "hex")]
+    #[test]
+    fn test_hex_storage_trait() {
+        use hexx::OffsetHexMode;
+
+        use super::hex::HexRectangleStorage;
+
+        for mode in [
+            OffsetHexMode::OddRows,
+            OffsetHexMode::EvenRows,
+            OffsetHexMode::OddColumns,
+            OffsetHexMode::EvenColumns,
+        ] {
+            let mut storage = HexRectangleStorage::new_with_mode(6, 4, mode);
+            fill_with_cells(&mut storage);
+            assert_cells_match(&storage);
+        }
+    }
+}
@@ -0,0 +1,431 @@
+use std::collections::HashMap;
+
+use glam::UVec2;
+
+#[cfg(feature = "hex")]
+use hexx::OffsetHexMode;
+
+#[cfg(feature = "hex")]
+use crate::cell::hex::HexTransform;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "bevy_reflect")]
+use bevy::prelude::Reflect;
+
+#[cfg(any(feature = "square", feature = "hex"))]
+use crate::cell::topology::Topology;
+use crate::{cell::Cell, error::LettucesError};
+
+#[cfg(feature = "hex")]
+use super::hex::{self, HexRectangleStorage};
+use super::{grid::Grid, GridStorage};
+
+/// Storage for layers where only a few scattered cells hold data, such as items on the floor.
+///
+/// Only the cells that were set are stored. [`SparseStorage::contains`] is only true for cells holding data, so generic
+/// algorithms see every other cell as outside the storage, while setting any cell always succeeds and adds it.
+/// Works with both square and hexagonal cells.
+///
+/// Iteration visits cells in row order, sorted by `y` and then `x`, so it does not depend on the order cells were set
+/// in. The cells are kept in a [`HashMap`] for fast access to single cells, so every iteration and region query scans
+/// the whole storage and sorts the cells it yields.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bevy_reflect", derive(Reflect))]
+pub struct SparseStorage<T> {
+    pub cells: HashMap<Cell, T>,
+}
+
+impl<T> SparseStorage<T> {
+    /// Construct a new empty [`SparseStorage`]
+    pub fn new() -> SparseStorage<T> {
+        SparseStorage {
+            cells: HashMap::new(),
+        }
+    }
+
+    /// Construct a new empty [`SparseStorage`] with room for at least `capacity` cells
+    pub fn with_capacity(capacity: usize) -> SparseStorage<T> {
+        SparseStorage {
+            cells: HashMap::with_capacity(capacity),
+        }
+    }
+
+    /// Construct a new [`SparseStorage`] by cloning every cell of another storage for which `keep` returns true
+    pub fn from_storage(
+        storage: &impl GridStorage<T>,
+        keep: impl Fn(&T) -> bool,
+    ) -> SparseStorage<T>
+    where
+        T: Clone,
+    {
+        SparseStorage {
+            cells: storage
+                .iter()
+                .filter(|(_, data)| keep(data))
+                .map(|(cell, data)| (cell, data.clone()))
+                .collect(),
+        }
+    }
+
+    /// The number of cells holding data
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Access the data at the given cell. Returns None if the cell holds no data
+    pub fn get(&self, cell: Cell) -> Option<&T> {
+        self.cells.get(&cell)
+    }
+
+    /// Access the data at the given cell mutably. Returns None if the cell holds no data
+    pub fn get_mut(&mut self, cell: Cell) -> Option<&mut T> {
+        self.cells.get_mut(&cell)
+    }
+
+    /// Sets the data at the given cell, returning the previous data if there was any
+    pub fn set(&mut self, cell: Cell, data: T) -> Option<T> {
+        self.cells.insert(cell, data)
+    }
+
+    /// Removes the data at the given cell, returning it if there was any
+    pub fn remove(&mut self, cell: Cell) -> Option<T> {
+        self.cells.remove(&cell)
+    }
+
+    /// Returns true if the given cell holds data
+    pub fn contains(&self, cell: Cell) -> bool {
+        self.cells.contains_key(&cell)
+    }
+
+    /// Removes the data from every cell
+    pub fn clear(&mut self) {
+        self.cells.clear();
+    }
+
+    /// Iterates over every cell holding data alongside its data, sorted by `y` and then `x`. Collects and sorts every
+    /// cell before yielding the first one
+    pub fn iter(&self) -> impl Iterator<Item = (Cell, &T)> {
+        sorted(self.cells.iter().map(|(cell, t)| (*cell, t)))
+    }
+
+    /// Iterates mutably over every cell holding data alongside its data, sorted by `y` and then `x`. Collects and
+    /// sorts every cell before yielding the first one
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Cell, &mut T)> {
+        sorted(self.cells.iter_mut().map(|(cell, t)| (*cell, t)))
+    }
+
+    /// Iterates over every cell holding data inside the rectangle between `min` and `max`, both included, sorted by
+    /// `y` and then `x`. Scans every cell of the storage but only sorts the cells inside the rectangle
+    pub fn in_rect(&self, min: Cell, max: Cell) -> impl Iterator<Item = (Cell, &T)> {
+        sorted(
+            self.cells
+                .iter()
+                .map(|(cell, t)| (*cell, t))
+                .filter(move |(cell, _)| {
+                    (min.x..=max.x).contains(&cell.x) && (min.y..=max.y).contains(&cell.y)
+                }),
+        )
+    }
+
+    /// Iterates over every cell holding data within `radius` steps of `center` in the given topology, sorted by `y`
+    /// and then `x`. Scans every cell of the storage but only sorts the cells in range
+    #[cfg(any(feature = "square", feature = "hex"))]
+    pub fn in_range(
+        &self,
+        center: Cell,
+        radius: u32,
+        topology: Topology,
+    ) -> impl Iterator<Item = (Cell, &T)> {
+        sorted(
+            self.cells
+                .iter()
+                .map(|(cell, t)| (*cell, t))
+                .filter(move |(cell, _)| topology.distance(center, *cell) <= radius),
+        )
+    }
+
+    /// The smallest and largest `x` and `y` of any cell holding data. Returns None if the storage is empty
+    pub fn bounds(&self) -> Option<(Cell, Cell)> {
+        let mut cells = self.cells.keys();
+        let first = *cells.next()?;
+        Some(cells.fold((first, first), |(min, max), cell| {
+            (
+                Cell::new(min.x.min(cell.x), min.y.min(cell.y)),
+                Cell::new(max.x.max(cell.x), max.y.max(cell.y)),
+            )
+        }))
+    }
+
+    /// The size of the smallest rectangle containing every cell holding data
+    pub fn dimensions(&self) -> UVec2 {
+        self.bounds().map_or(UVec2::ZERO, |(min, max)| {
+            // Measured in i64 so that bounds spanning every i32 cannot overflow
+            let size =
+                |min: i32, max: i32| (max as i64 - min as i64 + 1).min(u32::MAX as i64) as u32;
+            UVec2::new(size(min.x, max.x), size(min.y, max.y))
+        })
+    }
+
+    /// Moves the data into a dense [`Grid`] with the given size, using `x` as the column and `y` as the row. Cells
+    /// outside the grid are dropped
+    pub fn into_grid(self, rows: usize, cols: usize) -> Grid<Option<T>> {
+        let mut grid = Grid::new(rows, cols);
+        for (cell, data) in self.cells {
+            GridStorage::set(&mut grid, cell, Some(data));
+        }
+        grid
+    }
+
+    /// Moves the data into a dense [`HexRectangleStorage`] with the given size, indexed with the given offset mode.
+    /// Cells outside the storage are dropped
+    #[cfg(feature = "hex")]
+    pub fn into_hex_rectangle(
+        self,
+        x_size: usize,
+        y_size: usize,
+        mode: OffsetHexMode,
+    ) -> HexRectangleStorage<Option<T>> {
+        let mut storage = HexRectangleStorage::new_with_mode(x_size, y_size, mode);
+        for (cell, data) in self.cells {
+            storage.set(cell, Some(data));
+        }
+        storage
+    }
+
+    /// Rotates or reflects every cell around `pivot`, keeping the data with the moved cells. See [`HexTransform`]
+    #[cfg(feature = "hex")]
+    pub fn hex_transformed(self, transform: HexTransform, pivot: Cell) -> SparseStorage<T> {
+        self.cells
+            .into_iter()
+            .map(|(cell, data)| (transform.apply(cell, pivot), data))
+            .collect()
+    }
+}
+
+impl<T> Default for SparseStorage<T> {
+    fn default() -> Self {
+        SparseStorage::new()
+    }
+}
+
+/// Collects the entries and sorts them by `y` and then `x`
+fn sorted<R>(entries: impl Iterator<Item = (Cell, R)>) -> std::vec::IntoIter<(Cell, R)> {
+    let mut entries: Vec<(Cell, R)> = entries.collect();
+    entries.sort_unstable_by_key(|(cell, _)| (cell.y, cell.x));
+    entries.into_iter()
+}
+
+impl<T> GridStorage<T> for SparseStorage<T> {
+    fn get(&self, cell: Cell) -> Option<&T> {
+        self.get(cell)
+    }
+
+    fn get_mut(&mut self, cell: Cell) -> Option<&mut T> {
+        self.get_mut(cell)
+    }
+
+    fn set(&mut self, cell: Cell, data: T) -> Option<T> {
+        self.set(cell, data)
+    }
+
+    fn contains(&self, cell: Cell) -> bool {
+        self.contains(cell)
+    }
+
+    fn iter<'a>(&'a self) -> impl Iterator<Item = (Cell, &'a T)>
+    where
+        T: 'a,
+    {
+        self.iter()
+    }
+
+    fn iter_mut<'a>(&'a mut self) -> impl Iterator<Item = (Cell, &'a mut T)>
+    where
+        T: 'a,
+    {
+        self.iter_mut()
+    }
+
+    fn dimensions(&self) -> UVec2 {
+        self.dimensions()
+    }
+
+    /// Every cell can hold data, so this never fails
+    fn try_set(&mut self, cell: Cell, data: T) -> Result<Option<T>, LettucesError> {
+        Ok(self.set(cell, data))
+    }
+}
+
+/// Keeps every cell of the grid holding `Some`, using the column as `x` and the row as `y`
+impl<T> From<Grid<Option<T>>> for SparseStorage<T> {
+    fn from(grid: Grid<Option<T>>) -> Self {
+        let cols = grid.cols();
+        SparseStorage {
+            cells: grid
+                .into_vec()
+                .into_iter()
+                .enumerate()
+                .filter_map(|(i, t)| Some((Cell::new((i % cols) as i32, (i / cols) as i32), t?)))
+                .collect(),
+        }
+    }
+}
+
+/// Keeps every cell of the storage holding `Some`
+#[cfg(feature = "hex")]
+impl<T> From<HexRectangleStorage<Option<T>>> for SparseStorage<T> {
+    fn from(storage: HexRectangleStorage<Option<T>>) -> Self {
+        let cols = storage.grid.cols();
+        let mode = storage.offset_mode();
+        SparseStorage {
+            cells: storage
+                .grid
+                .into_vec()
+                .into_iter()
+                .enumerate()
+                .filter_map(|(i, t)| Some((hex::cell_at_index(mode, [i % cols, i / cols]), t?)))
+                .collect(),
+        }
+    }
+}
+
+impl<T> FromIterator<(Cell, T)> for SparseStorage<T> {
+    fn from_iter<I: IntoIterator<Item = (Cell, T)>>(iter: I) -> Self {
+        SparseStorage {
+            cells: iter.into_iter().collect(),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "square"))]
+mod tests {
+    use glam::UVec2;
+
+    use crate::cell::{square::SquareConnectivity, topology::Topology, Cell};
+    use crate::storage::{grid::Grid, GridStorage};
+
+    use super::SparseStorage;
+
+    #[test]
+    fn test_access_and_order() {
+        let mut map = SparseStorage::new();
+        assert_eq!(map.set(Cell::new(5, -3), 'a'), None);
+        assert_eq!(map.set(Cell::new(-2, 4), 'b'), None);
+        assert_eq!(map.set(Cell::new(1, -3), 'c'), None);
+        assert_eq!(map.set(Cell::new(5, -3), 'd'), Some('a'));
+
+        assert_eq!(map.len(), 3);
+        assert_eq!(map.get(Cell::new(-2, 4)), Some(&'b'));
+        assert!(map.get(Cell::new(0, 0)).is_none());
+        assert_eq!(
+            map.iter().collect::<Vec<_>>(),
+            vec![
+                (Cell::new(1, -3), &'c'),
+                (Cell::new(5, -3), &'d'),
+                (Cell::new(-2, 4), &'b')
+            ]
+        );
+        assert_eq!(map.bounds(), Some((Cell::new(-2, -3), Cell::new(5, 4))));
+        assert_eq!(map.dimensions(), UVec2::new(8, 8));
+
+        assert_eq!(map.remove(Cell::new(1, -3)), Some('c'));
+        assert!(!map.contains(Cell::new(1, -3)));
+
+        let far: SparseStorage<char> =
+            [(Cell::new(i32::MIN, 0), 'a'), (Cell::new(i32::MAX, 0), 'b')]
+                .into_iter()
+                .collect();
+        assert_eq!(far.dimensions(), UVec2::new(u32::MAX, 1));
+    }
+
+    #[test]
+    fn test_region_queries() {
+        let map: SparseStorage<u32> = (0..10).map(|i| (Cell::new(i, i), i as u32)).collect();
+
+        let rect: Vec<u32> = map
+            .in_rect(Cell::new(2, 0), Cell::new(4, 9))
+            .map(|(_, t)| *t)
+            .collect();
+        assert_eq!(rect, vec![2, 3, 4]);
+
+        let range = map.in_range(
+            Cell::new(5, 5),
+            2,
+            Topology::Square(SquareConnectivity::Four),
+        );
+        assert_eq!(range.map(|(_, t)| *t).collect::<Vec<_>>(), vec![4, 5, 6]);
+    }
+
+    #[test]
+    fn test_grid_conversion() {
+        let grid = Grid::from_vec(vec![None, Some(1), None, None, None, Some(2)], 3);
+        let map = SparseStorage::from(grid.clone());
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.get(Cell::new(2, 1)), Some(&2));
+        assert_eq!(map.clone().into_grid(2, 3), grid);
+
+        let dense = Grid::from_vec(vec![0, 0, 7, 0], 2);
+        let map = SparseStorage::from_storage(&dense, |t| *t != 0);
+        assert_eq!(
+            GridStorage::iter(&map).collect::<Vec<_>>(),
+            vec![(Cell::new(0, 1), &7)]
+        );
+    }
+
+    #[cfg(feature = "hex")]
+    #[test]
+    fn test_hex_conversion() {
+        use crate::storage::hex::HexRectangleStorage;
+        use hexx::OffsetHexMode;
+
+        for mode in [OffsetHexMode::OddColumns, OffsetHexMode::EvenColumns] {
+            let mut dense = HexRectangleStorage::new_with_mode(4, 3, mode);
+            let cells: Vec<Cell> = [[0, 0], [3, 0], [1, 2]]
+                .into_iter()
+                .map(|position| Cell::from_offset_coordinates(position, mode))
+                .collect();
+            for cell in cells.iter() {
+                assert_eq!(dense.set(*cell, Some(*cell)), Some(None));
+            }
+
+            let map = SparseStorage::from(dense.clone());
+            assert_eq!(map.len(), 3);
+            assert!(map.iter().all(|(cell, t)| cell == *t));
+
+            let back = map.into_hex_rectangle(4, 3, mode);
+            assert_eq!(back.grid, dense.grid);
+            assert_eq!(back.offset_mode(), mode);
+        }
+    }
+
+    #[cfg(feature = "hex")]
+    #[test]
+    fn test_hex_transform() {
+        use crate::cell::hex::HexTransform;
+
+        let prefab: SparseStorage<char> = [
+            (Cell::new(0, 0), 'a'),
+            (Cell::new(1, 0), 'b'),
+            (Cell::new(1, -1), 'c'),
+        ]
+        .into_iter()
+        .collect();
+        let rotated = prefab
+            .clone()
+            .hex_transformed(HexTransform::RotateCcw(1), Cell::ZERO);
+        assert_eq!(rotated.get(Cell::new(0, 0)), Some(&'a'));
+        assert_eq!(rotated.get(Cell::new(1, -1)), Some(&'b'));
+        assert_eq!(rotated.get(Cell::new(0, -1)), Some(&'c'));
+        assert_eq!(
+            rotated.hex_transformed(HexTransform::RotateCw(1), Cell::ZERO),
+            prefab
+        );
+    }
+}
@@ -0,0 +1,322 @@
+use glam::UVec2;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "bevy_reflect")]
+use bevy::prelude::Reflect;
+
+use crate::{
+    cell::Cell,
+    error::{row_length, LettucesError},
+};
+
+use super::{grid::Grid, GridStorage};
+
+/// Storage structure for a square map that is rectangular in nature with rows of the same length.
+///
+/// Cells are stored relative to `origin`. The cell at `origin` is the first element of the grid, x increases
+/// along a row and y increases with each row. Moving the origin allows storing maps with negative coordinates.
+#[derive(Hash, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bevy_reflect", derive(Reflect))]
+pub struct SquareStorage<T> {
+    pub grid: Grid<T>,
+    pub origin: Cell,
+}
+
+impl<T> SquareStorage<T> {
+    /// Construct a new [`SquareStorage`] from the default for the given data
+    pub fn new(x_size: usize, y_size: usize) -> SquareStorage<T>
+    where
+        T: Default,
+    {
+        SquareStorage {
+            grid: Grid::new(y_size, x_size),
+            origin: Cell::ORIGIN,
+        }
+    }
+
+    /// Construct a new [`SquareStorage`] by cloning the given data
+    pub fn new_uniform(x_size: usize, y_size: usize, data: T) -> SquareStorage<T>
+    where
+        T: Clone,
+    {
+        SquareStorage {
+            grid: Grid::init(y_size, x_size, data),
+            origin: Cell::ORIGIN,
+        }
+    }
+
+    /// Construct a new [`SquareStorage`] from a vec of vecs of data.
+    ///
+    /// Each inner vec is a row of the map, so `data[y][x]` is stored at `Cell::new(x, y)`
+    ///
+    /// # Panics
+    /// If the rows have different lengths. See [`SquareStorage::try_from_vec`]
+    pub fn new_from_vec(data: Vec<Vec<T>>) -> SquareStorage<T> {
+        let row_length = row_length(&data).unwrap_or_else(|error| panic!("{error}"));
+
+        SquareStorage {
+            grid: Grid::from_vec(data.into_iter().flatten().collect(), row_length),
+            origin: Cell::ORIGIN,
+        }
+    }
+
+    /// Construct a new [`SquareStorage`] from a vec of vecs of data like [`SquareStorage::new_from_vec`]. Returns
+    /// [`LettucesError::Empty`] if there is no data or [`LettucesError::Ragged`] if the rows have different lengths
+    pub fn try_from_vec(data: Vec<Vec<T>>) -> Result<SquareStorage<T>, LettucesError> {
+        if row_length(&data)? == 0 {
+            return Err(LettucesError::Empty);
+        }
+        Ok(SquareStorage::new_from_vec(data))
+    }
+
+    /// Moves the origin of the storage to the given cell. The first element of the grid will be accessed at `origin`
+    pub fn with_origin(mut self, origin: Cell) -> SquareStorage<T> {
+        self.origin = origin;
+        self
+    }
+
+    pub fn dimensions(&self) -> UVec2 {
+        UVec2 {
+            x: self.grid.cols() as u32,
+            y: self.grid.rows() as u32,
+        }
+    }
+
+    /// Returns the `[col, row]` index into the grid for the given cell if it is inside the storage
+    pub fn verify_access(&self, cell: Cell) -> Option<[usize; 2]> {
+        let local = Cell::new(
+            cell.x.checked_sub(self.origin.x)?,
+            cell.y.checked_sub(self.origin.y)?,
+        );
+        if local.x.is_negative()
+            || local.y.is_negative()
+            || local.x as usize >= self.grid.cols()
+            || local.y as usize >= self.grid.rows()
+        {
+            return None;
+        }
+        Some([local.x as usize, local.y as usize])
+    }
+
+    /// Access data inside the grid. Returns None if the cell is outside the storage
+    pub fn get(&self, cell: Cell) -> Option<&T> {
+        let access = self.verify_access(cell)?;
+        self.grid.get(access[1], access[0])
+    }
+
+    /// Access data mutably inside the grid. Returns None if the cell is outside the storage
+    pub fn get_mut(&mut self, cell: Cell) -> Option<&mut T> {
+        let access = self.verify_access(cell)?;
+        self.grid.get_mut(access[1], access[0])
+    }
+
+    /// Sets the data at the given Cell, returning the previous data. Returns None and drops the data if the cell is
+    /// outside the storage
+    pub fn set(&mut self, cell: Cell, data: T) -> Option<T> {
+        let t = self.get_mut(cell)?;
+        Some(std::mem::replace(t, data))
+    }
+
+    /// Returns a copy of the rectangle spanned by the two corner cells, both included. The rectangle is clipped to the
+    /// storage and the copy keeps the cells of the data, so its origin is the top left cell that was copied. Paste it
+    /// elsewhere with [`GridStorage::blit`]
+    pub fn copy_region(&self, corner: Cell, opposite_corner: Cell) -> SquareStorage<T>
+    where
+        T: Clone,
+    {
+        // Clipped in i64 so that corners far outside the storage cannot overflow
+        let clip = |a: i32, b: i32, origin: i32, len: usize| {
+            let min = (a.min(b) as i64 - origin as i64).clamp(0, len as i64);
+            let max = (a.max(b) as i64 - origin as i64).min(len as i64 - 1);
+            (min as usize, (max - min + 1).max(0) as usize)
+        };
+        let (col, cols) = clip(corner.x, opposite_corner.x, self.origin.x, self.grid.cols());
+        let (row, rows) = clip(corner.y, opposite_corner.y, self.origin.y, self.grid.rows());
+        SquareStorage {
+            grid: self
+                .grid
+                .copy_region(row as isize, col as isize, rows, cols),
+            origin: self.origin + Cell::new(col as i32, row as i32),
+        }
+    }
+}
+
+impl<T> GridStorage<T> for SquareStorage<T> {
+    fn get(&self, cell: Cell) -> Option<&T> {
+        self.get(cell)
+    }
+
+    fn get_mut(&mut self, cell: Cell) -> Option<&mut T> {
+        self.get_mut(cell)
+    }
+
+    fn set(&mut self, cell: Cell, data: T) -> Option<T> {
+        self.set(cell, data)
+    }
+
+    fn contains(&self, cell: Cell) -> bool {
+        self.verify_access(cell).is_some()
+    }
+
+    fn iter<'a>(&'a self) -> impl Iterator<Item = (Cell, &'a T)>
+    where
+        T: 'a,
+    {
+        let cols = self.grid.cols() as i32;
+        let origin = self.origin;
+        self.grid.iter().enumerate().map(move |(i, t)| {
+            let i = i as i32;
+            (origin + Cell::new(i % cols, i / cols), t)
+        })
+    }
+
+    fn iter_mut<'a>(&'a mut self) -> impl Iterator<Item = (Cell, &'a mut T)>
+    where
+        T: 'a,
+    {
+        let cols = self.grid.cols() as i32;
+        let origin = self.origin;
+        self.grid.iter_mut().enumerate().map(move |(i, t)| {
+            let i = i as i32;
+            (origin + Cell::new(i % cols, i / cols), t)
+        })
+    }
+
+    fn dimensions(&self) -> UVec2 {
+        self.dimensions()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::UVec2;
+
+    use crate::{cell::Cell, error::LettucesError, storage::GridStorage};
+
+    use super::SquareStorage;
+
+    #[test]
+    fn test_basic_access() {
+        let map = SquareStorage::<u32>::new(5, 3);
+
+        assert_eq!(map.dimensions(), UVec2::new(5, 3));
+        assert!(map.get(Cell::new(0, 0)).is_some());
+        assert!(map.get(Cell::new(4, 2)).is_some());
+
+        assert!(map.get(Cell::new(5, 0)).is_none());
+        assert!(map.get(Cell::new(0, 3)).is_none());
+        assert!(map.get(Cell::new(-1, 0)).is_none());
+        assert!(map.get(Cell::new(0, -1)).is_none());
+    }
+
+    #[test]
+    fn test_correct_access() {
+        let data = (0..4)
+            .map(|y| (0..6).map(|x| Cell::new(x, y)).collect())
+            .collect();
+        let map = SquareStorage::<Cell>::new_from_vec(data);
+
+        assert_eq!(map.dimensions(), UVec2::new(6, 4));
+        for cell in [
+            Cell::new(0, 0),
+            Cell::new(5, 0),
+            Cell::new(2, 3),
+            Cell::new(5, 3),
+        ] {
+            assert_eq!(map.get(cell), Some(&cell));
+        }
+    }
+
+    #[test]
+    fn test_origin_access() {
+        let mut map = SquareStorage::new_uniform(4, 4, 0).with_origin(Cell::new(-2, -2));
+
+        assert!(map.get(Cell::new(-2, -2)).is_some());
+        assert!(map.get(Cell::new(1, 1)).is_some());
+        assert!(map.get(Cell::new(2, 1)).is_none());
+        assert!(map.get(Cell::new(-3, 0)).is_none());
+
+        assert_eq!(map.set(Cell::new(-1, 0), 7), Some(0));
+        assert_eq!(map.grid[2][1], 7);
+        assert_eq!(map.set(Cell::new(-1, 5), 7), None);
+
+        let mut far =
+            SquareStorage::new_uniform(2, 2, 0).with_origin(Cell::new(i32::MIN, i32::MIN));
+        assert!(far.get(Cell::new(5, 5)).is_none());
+        assert_eq!(far.set(Cell::new(i32::MAX, 0), 7), None);
+        assert!(far.get(Cell::new(i32::MIN + 1, i32::MIN)).is_some());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_ragged_vec() {
+        SquareStorage::new_from_vec(vec![vec![1, 2, 3], vec![4, 5]]);
+    }
+
+    #[test]
+    fn test_try_from_vec() {
+        assert_eq!(
+            SquareStorage::try_from_vec(vec![vec![1, 2, 3], vec![4, 5]]).err(),
+            Some(LettucesError::Ragged {
+                row: 1,
+                expected: 3,
+                found: 2
+            })
+        );
+        assert_eq!(
+            SquareStorage::<u32>::try_from_vec(vec![]).err(),
+            Some(LettucesError::Empty)
+        );
+
+        let mut map = SquareStorage::try_from_vec(vec![vec![1, 2], vec![3, 4]]).unwrap();
+        assert_eq!(map.try_set(Cell::new(1, 1), 7), Ok(Some(4)));
+        assert_eq!(
+            map.try_set(Cell::new(2, 1), 7),
+            Err(LettucesError::OutOfBounds(Cell::new(2, 1)))
+        );
+        assert_eq!(map.try_get(Cell::new(1, 1)), Ok(&7));
+    }
+
+    #[test]
+    fn test_copy_and_blit() {
+        let data = (0..4)
+            .map(|y| (0..4).map(|x| Cell::new(x, y)).collect())
+            .collect();
+        let map = SquareStorage::<Cell>::new_from_vec(data).with_origin(Cell::new(-1, -1));
+
+        let prefab = map.copy_region(Cell::new(1, -3), Cell::new(0, 0));
+        assert_eq!(prefab.origin, Cell::new(0, -1));
+        assert_eq!(prefab.dimensions(), UVec2::new(2, 2));
+        assert_eq!(prefab.get(Cell::new(1, 0)), Some(&Cell::new(2, 1)));
+        assert_eq!(
+            map.copy_region(Cell::new(5, 5), Cell::new(6, 6))
+                .grid
+                .size(),
+            (0, 0)
+        );
+        let everything =
+            map.copy_region(Cell::new(i32::MIN, i32::MIN), Cell::new(i32::MAX, i32::MAX));
+        assert_eq!(everything.origin, map.origin);
+        assert_eq!(everything.grid, map.grid);
+        let far = SquareStorage::<Cell>::new(2, 2).with_origin(Cell::new(i32::MIN, i32::MIN));
+        assert!(far
+            .copy_region(Cell::new(i32::MAX, i32::MAX), Cell::new(i32::MAX - 1, 0))
+            .grid
+            .is_empty());
+
+        let mut target = SquareStorage::new_uniform(3, 3, Cell::ZERO);
+        target.blit(&prefab, Cell::new(2, 1));
+        assert_eq!(target.get(Cell::new(2, 0)), Some(&Cell::new(1, 0)));
+        assert_eq!(target.get(Cell::new(2, 1)), Some(&Cell::new(1, 1)));
+        assert_eq!(target.iter().filter(|(_, t)| **t != Cell::ZERO).count(), 2);
+
+        let mut stamp = SquareStorage::new(2, 2);
+        stamp.set(Cell::new(1, 1), Some(Cell::new(9, 9)));
+        target.blit_masked(&stamp, Cell::new(-1, -1));
+        assert_eq!(target.get(Cell::ZERO), Some(&Cell::new(9, 9)));
+        assert_eq!(target.get(Cell::new(2, 0)), Some(&Cell::new(1, 0)));
+    }
+}
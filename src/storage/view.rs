@@ -0,0 +1,454 @@
+use std::{
+    marker::PhantomData,
+    ops::{Index, IndexMut},
+    ptr::NonNull,
+};
+
+use super::grid::Grid;
+
+/// A borrowed rectangular region of a [`Grid`], indexed locally so `(0, 0)` is the top left of the region.
+///
+/// Created with [`Grid::view`]. Indexing uses `(row, col)` like [`Grid`].
+#[derive(Debug)]
+pub struct GridView<'a, T> {
+    /// Starts at the top left element of the view. Each row starts `stride` elements after the one before it
+    data: &'a [T],
+    stride: usize,
+    rows: usize,
+    cols: usize,
+}
+
+impl<'a, T> Clone for GridView<'a, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, T> Copy for GridView<'a, T> {}
+
+impl<'a, T> GridView<'a, T> {
+    pub(super) fn new(data: &'a [T], stride: usize, rows: usize, cols: usize) -> GridView<'a, T> {
+        if rows == 0 || cols == 0 {
+            return GridView {
+                data: &[],
+                stride,
+                rows: 0,
+                cols: 0,
+            };
+        }
+        GridView {
+            data: &data[..(rows - 1) * stride + cols],
+            stride,
+            rows,
+            cols,
+        }
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// Returns the size of the view as a two element tuple. First element are the number of rows and the second the
+    /// columns
+    pub fn size(&self) -> (usize, usize) {
+        (self.rows, self.cols)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows == 0
+    }
+
+    /// Access an element of the view. Returns None if the element is outside the view
+    pub fn get(&self, row: usize, col: usize) -> Option<&'a T> {
+        if row < self.rows && col < self.cols {
+            self.data.get(row * self.stride + col)
+        } else {
+            None
+        }
+    }
+
+    /// Returns a row of the view. Returns None if the row is outside the view
+    pub fn row(&self, row: usize) -> Option<&'a [T]> {
+        if row >= self.rows {
+            return None;
+        }
+        let start = row * self.stride;
+        Some(&self.data[start..start + self.cols])
+    }
+
+    /// Iterates over the rows of the view from top to bottom
+    pub fn iter_rows(&self) -> impl Iterator<Item = &'a [T]> {
+        let view = *self;
+        (0..self.rows).filter_map(move |row| view.row(row))
+    }
+
+    /// Iterates over every element of the view, row by row
+    pub fn iter(&self) -> impl Iterator<Item = &'a T> {
+        self.iter_rows().flatten()
+    }
+
+    /// Returns a smaller view inside this one, starting at `(row, col)` with the given size. Returns None if the
+    /// region does not fit inside this view
+    pub fn view(
+        &self,
+        row: usize,
+        col: usize,
+        rows: usize,
+        cols: usize,
+    ) -> Option<GridView<'a, T>> {
+        if row.checked_add(rows)? > self.rows || col.checked_add(cols)? > self.cols {
+            return None;
+        }
+        let start = (row * self.stride + col).min(self.data.len());
+        Some(GridView::new(&self.data[start..], self.stride, rows, cols))
+    }
+
+    /// Splits the view in two at the given row. The first view holds the rows before `row`.
+    ///
+    /// # Panics
+    /// If `row > rows`
+    pub fn split_at_row(&self, row: usize) -> (GridView<'a, T>, GridView<'a, T>) {
+        assert!(
+            row <= self.rows,
+            "row {} is outside a view with {} rows",
+            row,
+            self.rows
+        );
+        (
+            self.view(0, 0, row, self.cols).unwrap(),
+            self.view(row, 0, self.rows - row, self.cols).unwrap(),
+        )
+    }
+
+    /// Splits the view in two at the given column. The first view holds the columns before `col`.
+    ///
+    /// # Panics
+    /// If `col > cols`
+    pub fn split_at_col(&self, col: usize) -> (GridView<'a, T>, GridView<'a, T>) {
+        assert!(
+            col <= self.cols,
+            "col {} is outside a view with {} cols",
+            col,
+            self.cols
+        );
+        (
+            self.view(0, 0, self.rows, col).unwrap(),
+            self.view(0, col, self.rows, self.cols - col).unwrap(),
+        )
+    }
+
+    /// Copies the view into a new [`Grid`]
+    pub fn to_grid(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        Grid::from_vec(self.iter().cloned().collect(), self.cols)
+    }
+}
+
+impl<'a, T> Index<(usize, usize)> for GridView<'a, T> {
+    type Output = T;
+
+    fn index(&self, (row, col): (usize, usize)) -> &T {
+        self.get(row, col).unwrap_or_else(|| {
+            panic!(
+                "index ({}, {}) is outside a view of size ({}, {})",
+                row, col, self.rows, self.cols
+            )
+        })
+    }
+}
+
+/// A mutably borrowed rectangular region of a [`Grid`], indexed locally so `(0, 0)` is the top left of the region.
+///
+/// Created with [`Grid::view_mut`], [`Grid::split_at_row`] or [`Grid::split_at_col`]. Views that do not overlap can
+/// be used at the same time, for example on different threads. Indexing uses `(row, col)` like [`Grid`].
+#[derive(Debug)]
+pub struct GridViewMut<'a, T> {
+    /// Points at the top left element of the view. Each row starts `stride` elements after the one before it. Views
+    /// split by column share rows, so the data is held as a pointer rather than a slice
+    data: NonNull<T>,
+    stride: usize,
+    rows: usize,
+    cols: usize,
+    marker: PhantomData<&'a mut [T]>,
+}
+
+// Safety: a view only hands out access to its own elements, which no other view can reach, so it is as thread safe as
+// the `&mut [T]` it was made from
+unsafe impl<'a, T: Send> Send for GridViewMut<'a, T> {}
+unsafe impl<'a, T: Sync> Sync for GridViewMut<'a, T> {}
+
+impl<'a, T> GridViewMut<'a, T> {
+    pub(super) fn new(
+        data: &'a mut [T],
+        stride: usize,
+        rows: usize,
+        cols: usize,
+    ) -> GridViewMut<'a, T> {
+        let (rows, cols) = if rows == 0 || cols == 0 {
+            (0, 0)
+        } else {
+            (rows, cols)
+        };
+        debug_assert!(rows == 0 || (rows - 1) * stride + cols <= data.len());
+        GridViewMut {
+            data: NonNull::from(data).cast(),
+            stride,
+            rows,
+            cols,
+            marker: PhantomData,
+        }
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// Returns the size of the view as a two element tuple. First element are the number of rows and the second the
+    /// columns
+    pub fn size(&self) -> (usize, usize) {
+        (self.rows, self.cols)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows == 0
+    }
+
+    /// Access an element of the view. Returns None if the element is outside the view
+    pub fn get(&self, row: usize, col: usize) -> Option<&T> {
+        self.row(row)?.get(col)
+    }
+
+    /// Access an element of the view mutably. Returns None if the element is outside the view
+    pub fn get_mut(&mut self, row: usize, col: usize) -> Option<&mut T> {
+        self.row_mut(row)?.get_mut(col)
+    }
+
+    /// Returns a row of the view. Returns None if the row is outside the view
+    pub fn row(&self, row: usize) -> Option<&[T]> {
+        // Safety: the row is inside the view, which borrows its elements for 'a
+        (row < self.rows)
+            .then(|| unsafe { std::slice::from_raw_parts(self.row_ptr(row), self.cols) })
+    }
+
+    /// Returns a row of the view mutably. Returns None if the row is outside the view
+    pub fn row_mut(&mut self, row: usize) -> Option<&mut [T]> {
+        // Safety: the row is inside the view, which borrows its elements mutably for 'a
+        (row < self.rows)
+            .then(|| unsafe { std::slice::from_raw_parts_mut(self.row_ptr(row), self.cols) })
+    }
+
+    /// Iterates over the rows of the view from top to bottom
+    pub fn iter_rows(&self) -> impl Iterator<Item = &[T]> {
+        (0..self.rows).filter_map(move |row| self.row(row))
+    }
+
+    /// Iterates mutably over the rows of the view from top to bottom
+    pub fn iter_rows_mut(&mut self) -> impl Iterator<Item = &mut [T]> {
+        let (data, stride, cols) = (self.data, self.stride, self.cols);
+        // Safety: every row is inside the view and yielded once while the view is borrowed, so the rows never alias
+        (0..self.rows).map(move |row| unsafe {
+            std::slice::from_raw_parts_mut(data.as_ptr().add(row * stride), cols)
+        })
+    }
+
+    /// Iterates over every element of the view, row by row
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.iter_rows().flatten()
+    }
+
+    /// Iterates mutably over every element of the view, row by row
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.iter_rows_mut().flatten()
+    }
+
+    /// Fills the view with elements by cloning `value`
+    pub fn fill(&mut self, value: T)
+    where
+        T: Clone,
+    {
+        for row in self.iter_rows_mut() {
+            row.fill(value.clone());
+        }
+    }
+
+    /// Narrows the view to the region starting at `(row, col)` with the given size. Returns None if the region does
+    /// not fit inside this view
+    pub fn into_view_mut(
+        self,
+        row: usize,
+        col: usize,
+        rows: usize,
+        cols: usize,
+    ) -> Option<GridViewMut<'a, T>> {
+        if row.checked_add(rows)? > self.rows || col.checked_add(cols)? > self.cols {
+            return None;
+        }
+        Some(self.part(row * self.stride + col, rows, cols))
+    }
+
+    /// Splits the view in two at the given row. The first view holds the rows before `row`.
+    ///
+    /// # Panics
+    /// If `row > rows`
+    pub fn split_at_row(self, row: usize) -> (GridViewMut<'a, T>, GridViewMut<'a, T>) {
+        assert!(
+            row <= self.rows,
+            "row {} is outside a view with {} rows",
+            row,
+            self.rows
+        );
+        (
+            self.part(0, row, self.cols),
+            self.part(row * self.stride, self.rows - row, self.cols),
+        )
+    }
+
+    /// Splits the view in two at the given column. The first view holds the columns before `col`.
+    ///
+    /// # Panics
+    /// If `col > cols`
+    pub fn split_at_col(self, col: usize) -> (GridViewMut<'a, T>, GridViewMut<'a, T>) {
+        assert!(
+            col <= self.cols,
+            "col {} is outside a view with {} cols",
+            col,
+            self.cols
+        );
+        (
+            self.part(0, self.rows, col),
+            self.part(col, self.rows, self.cols - col),
+        )
+    }
+
+    /// Copies the view into a new [`Grid`]
+    pub fn to_grid(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        Grid::from_vec(self.iter().cloned().collect(), self.cols)
+    }
+
+    /// A pointer to the first element of the given row, which must be inside the view
+    fn row_ptr(&self, row: usize) -> *mut T {
+        // Safety: the row is inside the view, so the offset stays inside the borrowed data
+        unsafe { self.data.as_ptr().add(row * self.stride) }
+    }
+
+    /// A view of part of this one starting `offset` elements after its first element. The region must fit inside
+    /// this view and must not overlap any other part handed out
+    fn part(&self, offset: usize, rows: usize, cols: usize) -> GridViewMut<'a, T> {
+        let (offset, rows, cols) = if rows == 0 || cols == 0 {
+            (0, 0, 0)
+        } else {
+            (offset, rows, cols)
+        };
+        GridViewMut {
+            // Safety: the region fits inside this view, so the offset stays inside the borrowed data
+            data: unsafe { NonNull::new_unchecked(self.data.as_ptr().add(offset)) },
+            stride: self.stride,
+            rows,
+            cols,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, T> Index<(usize, usize)> for GridViewMut<'a, T> {
+    type Output = T;
+
+    fn index(&self, (row, col): (usize, usize)) -> &T {
+        let size = self.size();
+        self.get(row, col).unwrap_or_else(|| {
+            panic!(
+                "index ({}, {}) is outside a view of size ({}, {})",
+                row, col, size.0, size.1
+            )
+        })
+    }
+}
+
+impl<'a, T> IndexMut<(usize, usize)> for GridViewMut<'a, T> {
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut T {
+        let size = self.size();
+        self.get_mut(row, col).unwrap_or_else(|| {
+            panic!(
+                "index ({}, {}) is outside a view of size ({}, {})",
+                row, col, size.0, size.1
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::grid;
+    use crate::storage::grid::Grid;
+
+    #[test]
+    fn test_view() {
+        let grid: Grid<u32> = grid![[1, 2, 3, 4][5, 6, 7, 8][9, 10, 11, 12]];
+
+        let view = grid.view(1, 1, 2, 2).unwrap();
+        assert_eq!(view.size(), (2, 2));
+        assert_eq!(view[(0, 0)], 6);
+        assert_eq!(view[(1, 1)], 11);
+        assert_eq!(view.get(2, 0), None);
+        assert_eq!(
+            view.iter_rows().collect::<Vec<_>>(),
+            vec![&[6, 7][..], &[10, 11][..]]
+        );
+        assert_eq!(view.to_grid(), grid![[6, 7][10, 11]]);
+
+        let (top, bottom) = grid.as_view().split_at_row(1);
+        assert_eq!(top.iter().copied().collect::<Vec<_>>(), vec![1, 2, 3, 4]);
+        assert_eq!(bottom.size(), (2, 4));
+        let (left, right) = bottom.split_at_col(3);
+        assert_eq!(left.view(1, 2, 1, 1).unwrap()[(0, 0)], 11);
+        assert_eq!(right.iter().copied().collect::<Vec<_>>(), vec![8, 12]);
+
+        assert!(grid.view(2, 0, 2, 1).is_none());
+        assert!(grid.view(0, 3, 1, 2).is_none());
+        assert!(grid.view(3, 4, 0, 0).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_view_mut() {
+        let mut grid: Grid<u32> = grid![[1, 2, 3][4, 5, 6][7, 8, 9]];
+
+        let mut view = grid.view_mut(0, 1, 2, 2).unwrap();
+        view[(1, 1)] = 60;
+        view.row_mut(0).unwrap()[0] = 20;
+        assert_eq!(grid, grid![[1, 20, 3][4, 5, 60][7, 8, 9]]);
+
+        let (mut left, right) = grid.split_at_col(1);
+        let (mut top_right, mut bottom_right) = right.split_at_row(2);
+        left.fill(0);
+        top_right.iter_mut().for_each(|t| *t += 100);
+        bottom_right[(0, 1)] = 90;
+        assert_eq!(grid, grid![[0, 120, 103][0, 105, 160][0, 8, 90]]);
+
+        let (top, _) = grid.split_at_row(1);
+        let corner = top.into_view_mut(0, 1, 1, 2).unwrap();
+        assert_eq!(corner.to_grid(), Grid::from_vec(vec![120, 103], 2));
+
+        // Views split by column share rows but can still be filled on different threads
+        let (mut left, mut right) = grid.split_at_col(2);
+        std::thread::scope(|scope| {
+            scope.spawn(|| left.iter_rows_mut().for_each(|row| row.fill(1)));
+            scope.spawn(|| right.fill(2));
+        });
+        assert_eq!(grid, grid![[1, 1, 2][1, 1, 2][1, 1, 2]]);
+        assert!(grid.split_at_col(3).1.is_empty());
+        assert!(grid.split_at_row(3).1.is_empty());
+    }
+}